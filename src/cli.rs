@@ -1,14 +1,22 @@
 mod config;
 mod cli_utils;
+mod export;
+mod frontmatter_filter;
+mod git_sync;
+mod note_history;
+mod path_matcher;
+mod postprocessor;
+mod search_index;
 mod service;
 mod template_processor;
+mod vault_dump;
 
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 use serde_json;
 use std::collections::HashMap;
 
-use config::{load_config, save_config, Config};
+use config::{resolve_config, save_profile, ConfigOverrides, Profile};
 use cli_utils::resolve_content;
 use service::{
     ObsidianService,
@@ -16,13 +24,31 @@ use service::{
     CreateNoteFromTemplateRequest,
     CreateOrUpdateNoteRequest,
     DeleteNotesItemRequest,
+    DescribeNoteTemplateRequest,
+    ExportNoteRequest,
+    ExportVaultRequest,
+    FacetDistributionRequest,
     FindRelatedNotesRequest,
+    GetBacklinksRequest,
     GetDailyNoteRequest,
+    GetDailyNotesRangeRequest,
+    GetNoteFrontmatterRequest,
     GetNotePropertiesRequest,
+    DumpVaultRequest,
+    ListNoteHistoryRequest,
     ListNotesDirectoryRequest,
+    LoadVaultRequest,
+    MoveNoteRequest,
+    PullVaultRequest,
+    PushVaultRequest,
+    QueryNotesRequest,
     ReadNotesFileRequest,
+    ReindexVaultRequest,
+    SearchRankedRequest,
     ReplaceTextInNoteRequest,
+    RestoreNoteVersionRequest,
     SearchVaultRequest,
+    UpdateNoteFrontmatterRequest,
     UpdateNotePropertiesRequest,
 };
 
@@ -31,6 +57,31 @@ use service::{
 #[command(about = "CLI for Obsidian vault operations", long_about = None)]
 #[command(arg_required_else_help = true)]
 struct Cli {
+    /// Named vault profile to use. Overrides OBSIDIAN_PROFILE and the config's default_profile.
+    #[arg(long, global = true)]
+    profile: Option<String>,
+    /// Vault path. Overrides VAULT_LOCATION and the saved config for this invocation only.
+    #[arg(long, global = true)]
+    vault_path: Option<String>,
+    #[arg(long, global = true)]
+    daily_notes_path: Option<String>,
+    #[arg(long, global = true)]
+    weekly_notes_path: Option<String>,
+    #[arg(long, global = true)]
+    monthly_notes_path: Option<String>,
+    #[arg(long, global = true)]
+    templates_path: Option<String>,
+    #[arg(long, global = true)]
+    git_remote: Option<String>,
+    #[arg(long, global = true)]
+    git_branch: Option<String>,
+    /// Patterns always excluded from search/related-notes traversal (repeatable)
+    #[arg(long, global = true)]
+    default_exclude_patterns: Option<Vec<String>>,
+    /// Frontmatter key=value merged into every note written via create-or-update-note, without overwriting keys the note already has (repeatable). Value can be JSON.
+    #[arg(long, global = true)]
+    default_frontmatter: Option<Vec<String>>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -60,6 +111,14 @@ enum Commands {
     DeleteNotesItem {
         path: String,
     },
+    /// Move or rename a note, rewriting wikilinks that point at it
+    MoveNote {
+        from: String,
+        to: String,
+        /// Skip rewriting [[wikilinks]] across the vault
+        #[arg(long)]
+        no_update_links: bool,
+    },
     /// Create or update a note
     CreateOrUpdateNote {
         path: String,
@@ -78,12 +137,35 @@ enum Commands {
         /// overwrite | append | prepend
         #[arg(long, default_value = "overwrite")]
         mode: String,
+        /// Skip snapshotting the previous content to note history
+        #[arg(long)]
+        skip_history: bool,
+    },
+    /// List saved history versions of a note
+    ListNoteHistory {
+        path: String,
+    },
+    /// Atomically restore a note to a previously saved version
+    RestoreNoteVersion {
+        path: String,
+        /// Timestamp of the version to restore, as returned by list-note-history
+        timestamp: String,
     },
     /// Get daily note for a date
     GetDailyNote {
         #[arg(long, default_value = "today")]
         date: String,
     },
+    /// Get daily notes across an inclusive date range
+    GetDailyNotesRange {
+        #[arg(long)]
+        from: String,
+        #[arg(long)]
+        to: String,
+        /// Also concatenate found bodies into a single rollup string
+        #[arg(long)]
+        rollup: bool,
+    },
     /// Search vault for text
     SearchVault {
         query: String,
@@ -91,12 +173,75 @@ enum Commands {
         scope: Option<Vec<String>>,
         #[arg(long)]
         path_filter: Option<String>,
+        /// Typo-tolerant matching for the content scope
+        #[arg(long)]
+        fuzzy: bool,
+        #[arg(long, default_value = "50")]
+        limit: Option<u32>,
+        #[arg(long, default_value = "0")]
+        offset: Option<u32>,
+        /// path:/rootfilesin:/glob pattern a path must match (repeatable)
+        #[arg(long)]
+        include: Option<Vec<String>>,
+        /// path:/rootfilesin:/glob pattern a path must not match (repeatable)
+        #[arg(long)]
+        exclude: Option<Vec<String>>,
+        /// Frontmatter predicate, e.g. "status = done" (repeatable)
+        #[arg(long)]
+        filters: Option<Vec<String>>,
+        /// How to combine multiple --filters: "and" (default) or "or"
+        #[arg(long)]
+        filter_mode: Option<String>,
+    },
+    /// Rank notes purely by BM25 relevance, with title/tag matches boosted
+    SearchRanked {
+        query: String,
+        #[arg(long)]
+        path_filter: Option<String>,
+        /// Typo-tolerant matching
+        #[arg(long)]
+        fuzzy: bool,
+        #[arg(long, default_value = "50")]
+        limit: Option<u32>,
+        #[arg(long, default_value = "0")]
+        offset: Option<u32>,
+    },
+    /// Rebuild or refresh the on-disk search index
+    ReindexVault {
+        /// Discard the existing index and rescan the whole vault
+        #[arg(long)]
+        full: bool,
+    },
+    /// Query notes by a JSONPath expression over their frontmatter
+    QueryNotes {
+        query: String,
+        #[arg(long)]
+        path_filter: Option<String>,
+        /// Frontmatter keys to project into each result (repeatable)
+        #[arg(long)]
+        select: Option<Vec<String>>,
     },
     /// Find notes related to a source note
     FindRelatedNotes {
         path: String,
         #[arg(long)]
         on: Option<Vec<String>>,
+        /// path:/rootfilesin:/glob pattern a candidate must match (repeatable)
+        #[arg(long)]
+        include: Option<Vec<String>>,
+        /// path:/rootfilesin:/glob pattern a candidate must not match (repeatable)
+        #[arg(long)]
+        exclude: Option<Vec<String>>,
+    },
+    /// Tabulate distinct values and counts of a frontmatter property
+    GetFacetDistribution {
+        property: String,
+        #[arg(long)]
+        path_filter: Option<String>,
+    },
+    /// List notes that link to the given note
+    GetBacklinks {
+        path: String,
     },
     /// Replace text in a note
     ReplaceTextInNote {
@@ -131,6 +276,17 @@ enum Commands {
     GetNoteProperties {
         path: String,
     },
+    /// Parse and return a note's frontmatter block as JSON
+    GetNoteFrontmatter {
+        path: String,
+    },
+    /// Merge a JSON object into a note's existing frontmatter
+    UpdateNoteFrontmatter {
+        path: String,
+        /// Key=value (repeatable). Value can be JSON.
+        #[arg(long)]
+        set: Vec<String>,
+    },
     /// Create note from template
     CreateNoteFromTemplate {
         path: String,
@@ -138,9 +294,54 @@ enum Commands {
         /// key=value for template variables (repeatable)
         #[arg(long)]
         var: Option<Vec<String>>,
+        /// strftime format for the built-in 'date' variable (default ISO-8601)
+        #[arg(long)]
+        date_format: Option<String>,
+        /// strftime format for the built-in 'time' variable (default ISO-8601)
+        #[arg(long)]
+        time_format: Option<String>,
+        /// How 'variables' is merged into the template's own frontmatter: auto (default), always, or never
+        #[arg(long)]
+        frontmatter_strategy: Option<String>,
     },
-    /// List template files
+    /// List template files, each with its required/optional variables
     ListNotesTemplates,
+    /// Show a single template's required/optional variables
+    DescribeNoteTemplate {
+        /// Template path (same rules as CreateNoteFromTemplate's template_path)
+        template_path: String,
+    },
+    /// Show the effective configuration after merging flags, environment, config file, and defaults
+    ShowConfig,
+    /// Commit and push vault changes to the configured git remote
+    Push {
+        /// Commit message (an auto-generated timestamp message is used if omitted)
+        #[arg(long)]
+        message: Option<String>,
+    },
+    /// Fetch and merge vault changes from the configured git remote
+    Pull,
+    /// Export the entire vault into a versioned, gzipped-tar archive
+    DumpVault {
+        /// Output archive path (default: vault-dump-<timestamp>.tar.gz)
+        #[arg(long)]
+        output_path: Option<String>,
+    },
+    /// Restore a vault from an archive created by dump-vault
+    LoadVault {
+        input_path: String,
+        /// Overwrite existing files with the archive's contents
+        #[arg(long)]
+        overwrite: bool,
+    },
+    /// Convert a note's wikilinks/embeds into portable standard markdown
+    ExportNote {
+        path: String,
+    },
+    /// Export the entire vault into portable standard markdown
+    ExportVault {
+        destination: String,
+    },
 }
 
 fn print_json<T: serde::Serialize>(value: &T) -> Result<()> {
@@ -192,14 +393,21 @@ fn parse_key_value_pairs(pairs: Vec<String>) -> Result<HashMap<String, serde_jso
     Ok(map)
 }
 
-fn parse_template_vars(pairs: Option<Vec<String>>) -> HashMap<String, String> {
-    let mut map = HashMap::new();
-    for s in pairs.unwrap_or_default() {
-        if let Some((k, v)) = s.split_once('=') {
-            map.insert(k.to_string(), v.to_string());
-        }
+fn overrides_from_cli(cli: &Cli) -> ConfigOverrides {
+    ConfigOverrides {
+        profile: cli.profile.clone(),
+        vault_path: cli.vault_path.clone(),
+        daily_notes_path: cli.daily_notes_path.clone(),
+        weekly_notes_path: cli.weekly_notes_path.clone(),
+        monthly_notes_path: cli.monthly_notes_path.clone(),
+        templates_path: cli.templates_path.clone(),
+        git_remote: cli.git_remote.clone(),
+        git_branch: cli.git_branch.clone(),
+        default_exclude_patterns: cli.default_exclude_patterns.clone(),
+        default_frontmatter: cli.default_frontmatter.clone().map(|pairs| {
+            parse_key_value_pairs(pairs).unwrap_or_default().into_iter().collect()
+        }),
     }
-    map
 }
 
 fn run() -> Result<()> {
@@ -207,31 +415,46 @@ fn run() -> Result<()> {
 
     match &cli.command {
         Commands::SetVaultPath { path } => {
-            let config = Config {
+            let profile_name = cli.profile.clone().unwrap_or_else(|| "default".to_string());
+            let profile = Profile {
                 vault_path: path.clone(),
                 daily_notes_path: None,
                 weekly_notes_path: None,
                 monthly_notes_path: None,
                 templates_path: None,
+                git_remote: None,
+                git_branch: None,
+                default_exclude_patterns: None,
+                default_frontmatter: None,
             };
-            save_config(&config)?;
-            println!("Vault path set to: {}", path);
+            save_profile(&profile_name, profile)?;
+            println!("Vault path for profile '{}' set to: {}", profile_name, path);
+            return Ok(());
+        }
+        Commands::ShowConfig => {
+            let config = resolve_config(overrides_from_cli(&cli))?;
+            print_json(&config)?;
             return Ok(());
         }
         _ => {}
     }
 
-    let config = load_config()?;
+    let config = resolve_config(overrides_from_cli(&cli))?;
     let service = ObsidianService::new(
         &config.vault_path,
         config.daily_notes_path.as_deref(),
         config.weekly_notes_path.as_deref(),
         config.monthly_notes_path.as_deref(),
         config.templates_path.as_deref(),
+        config.git_remote.as_deref(),
+        config.git_branch.as_deref(),
+        config.default_exclude_patterns.clone(),
+        config.default_frontmatter.clone(),
     )?;
 
     match &cli.command {
         Commands::SetVaultPath { .. } => unreachable!(),
+        Commands::ShowConfig => unreachable!(),
 
         Commands::ListNotesDirectory { path, limit, offset, recursive } => {
             let req = ListNotesDirectoryRequest {
@@ -256,7 +479,17 @@ fn run() -> Result<()> {
             print_json(&result)?;
         }
 
-        Commands::CreateOrUpdateNote { path, content, content_file, content_stdin, frontmatter, mode } => {
+        Commands::MoveNote { from, to, no_update_links } => {
+            let req = MoveNoteRequest {
+                from: from.clone(),
+                to: to.clone(),
+                update_links: Some(!no_update_links),
+            };
+            let result = service.move_note_impl(req);
+            print_json(&result)?;
+        }
+
+        Commands::CreateOrUpdateNote { path, content, content_file, content_stdin, frontmatter, mode, skip_history } => {
             let content = if *content_stdin {
                 resolve_content("-")?
             } else if let Some(ref f) = content_file {
@@ -274,36 +507,114 @@ fn run() -> Result<()> {
                 content,
                 frontmatter,
                 mode: Some(mode.clone()),
+                skip_history: Some(*skip_history),
             };
             let result = service.create_or_update_note_impl(req);
             print_json(&result)?;
         }
 
+        Commands::ListNoteHistory { path } => {
+            let req = ListNoteHistoryRequest { path: path.clone() };
+            let result = service.list_note_history_impl(req);
+            print_json(&result)?;
+        }
+
+        Commands::RestoreNoteVersion { path, timestamp } => {
+            let req = RestoreNoteVersionRequest {
+                path: path.clone(),
+                timestamp: timestamp.clone(),
+            };
+            let result = service.restore_note_version_impl(req);
+            print_json(&result)?;
+        }
+
         Commands::GetDailyNote { date } => {
             let req = GetDailyNoteRequest { date: Some(date.clone()) };
             let result = service.get_daily_note_impl(req);
             print_json(&result)?;
         }
 
-        Commands::SearchVault { query, scope, path_filter } => {
+        Commands::GetDailyNotesRange { from, to, rollup } => {
+            let req = GetDailyNotesRangeRequest {
+                from: from.clone(),
+                to: to.clone(),
+                rollup: Some(*rollup),
+            };
+            let result = service.get_daily_notes_range_impl(req);
+            print_json(&result)?;
+        }
+
+        Commands::SearchVault { query, scope, path_filter, fuzzy, limit, offset, include, exclude, filters, filter_mode } => {
             let req = SearchVaultRequest {
                 query: query.clone(),
                 scope: scope.clone(),
                 path_filter: path_filter.clone(),
+                fuzzy: Some(*fuzzy),
+                limit: *limit,
+                offset: *offset,
+                include: include.clone(),
+                exclude: exclude.clone(),
+                filters: filters.clone(),
+                filter_mode: filter_mode.clone(),
             };
             let result = service.search_vault_impl(req);
             print_json(&result)?;
         }
 
-        Commands::FindRelatedNotes { path, on } => {
+        Commands::SearchRanked { query, path_filter, fuzzy, limit, offset } => {
+            let req = SearchRankedRequest {
+                query: query.clone(),
+                path_filter: path_filter.clone(),
+                fuzzy: Some(*fuzzy),
+                limit: *limit,
+                offset: *offset,
+            };
+            let result = service.search_ranked_impl(req);
+            print_json(&result)?;
+        }
+
+        Commands::ReindexVault { full } => {
+            let req = ReindexVaultRequest { full: Some(*full) };
+            let result = service.reindex_vault_impl(req);
+            print_json(&result)?;
+        }
+
+        Commands::QueryNotes { query, path_filter, select } => {
+            let req = QueryNotesRequest {
+                query: query.clone(),
+                path_filter: path_filter.clone(),
+                select: select.clone(),
+            };
+            let result = service.query_notes_impl(req);
+            print_json(&result)?;
+        }
+
+        Commands::FindRelatedNotes { path, on, include, exclude } => {
             let req = FindRelatedNotesRequest {
                 path: path.clone(),
                 on: on.clone(),
+                include: include.clone(),
+                exclude: exclude.clone(),
             };
             let result = service.find_related_notes_impl(req);
             print_json(&result)?;
         }
 
+        Commands::GetFacetDistribution { property, path_filter } => {
+            let req = FacetDistributionRequest {
+                property: property.clone(),
+                path_filter: path_filter.clone(),
+            };
+            let result = service.get_facet_distribution_impl(req);
+            print_json(&result)?;
+        }
+
+        Commands::GetBacklinks { path } => {
+            let req = GetBacklinksRequest { path: path.clone() };
+            let result = service.get_backlinks_impl(req);
+            print_json(&result)?;
+        }
+
         Commands::ReplaceTextInNote { path, find, replace, replace_all } => {
             let replace = resolve_content(replace)?;
             let req = ReplaceTextInNoteRequest {
@@ -344,12 +655,31 @@ fn run() -> Result<()> {
             print_json(&result)?;
         }
 
-        Commands::CreateNoteFromTemplate { path, template_path, var } => {
-            let variables = parse_template_vars(var.clone());
+        Commands::GetNoteFrontmatter { path } => {
+            let req = GetNoteFrontmatterRequest { path: path.clone() };
+            let result = service.get_note_frontmatter_impl(req);
+            print_json(&result)?;
+        }
+
+        Commands::UpdateNoteFrontmatter { path, set } => {
+            let frontmatter = parse_key_value_pairs(set.clone())?;
+            let req = UpdateNoteFrontmatterRequest {
+                path: path.clone(),
+                frontmatter: frontmatter.into_iter().collect(),
+            };
+            let result = service.update_note_frontmatter_impl(req);
+            print_json(&result)?;
+        }
+
+        Commands::CreateNoteFromTemplate { path, template_path, var, date_format, time_format, frontmatter_strategy } => {
+            let variables = parse_key_value_pairs(var.clone().unwrap_or_default())?;
             let req = CreateNoteFromTemplateRequest {
                 path: path.clone(),
                 template_path: template_path.clone(),
-                variables: if variables.is_empty() { None } else { Some(variables) },
+                variables: if variables.is_empty() { None } else { Some(serde_json::Value::Object(variables.into_iter().collect())) },
+                date_format: date_format.clone(),
+                time_format: time_format.clone(),
+                frontmatter_strategy: frontmatter_strategy.clone(),
             };
             let result = service.create_note_from_template_impl(req);
             print_json(&result)?;
@@ -359,6 +689,50 @@ fn run() -> Result<()> {
             let result = service.list_notes_templates_impl();
             print_json(&result)?;
         }
+
+        Commands::DescribeNoteTemplate { template_path } => {
+            let req = DescribeNoteTemplateRequest { template_path: template_path.clone() };
+            let result = service.describe_note_template_impl(req);
+            print_json(&result)?;
+        }
+
+        Commands::Push { message } => {
+            let req = PushVaultRequest { message: message.clone() };
+            let result = service.push_vault_impl(req);
+            print_json(&result)?;
+        }
+
+        Commands::Pull => {
+            let result = service.pull_vault_impl(PullVaultRequest {});
+            print_json(&result)?;
+        }
+
+        Commands::DumpVault { output_path } => {
+            let req = DumpVaultRequest { output_path: output_path.clone() };
+            let result = service.dump_vault_impl(req);
+            print_json(&result)?;
+        }
+
+        Commands::LoadVault { input_path, overwrite } => {
+            let req = LoadVaultRequest {
+                input_path: input_path.clone(),
+                overwrite: Some(*overwrite),
+            };
+            let result = service.load_vault_impl(req);
+            print_json(&result)?;
+        }
+
+        Commands::ExportNote { path } => {
+            let req = ExportNoteRequest { path: path.clone() };
+            let result = service.export_note_impl(req);
+            print_json(&result)?;
+        }
+
+        Commands::ExportVault { destination } => {
+            let req = ExportVaultRequest { destination: destination.clone() };
+            let result = service.export_vault_impl(req);
+            print_json(&result)?;
+        }
     }
 
     Ok(())
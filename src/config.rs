@@ -1,13 +1,16 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
 const CONFIG_DIR: &str = "obsidianclidigit1024";
 const CONFIG_FILE: &str = "config.json";
+const DEFAULT_PROFILE_NAME: &str = "default";
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Config {
+/// One named vault's settings: its own vault path plus notes/template sub-paths.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Profile {
     pub vault_path: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub daily_notes_path: Option<String>,
@@ -17,6 +20,90 @@ pub struct Config {
     pub monthly_notes_path: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub templates_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_remote: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_branch: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_exclude_patterns: Option<Vec<String>>,
+    /// Frontmatter keys/values merged into every note written via
+    /// create_or_update_note, without overwriting keys the note already has.
+    /// Stored as a JSON object so arbitrary value types (strings, lists...) round-trip.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_frontmatter: Option<serde_json::Map<String, serde_json::Value>>,
+}
+
+/// Pre-profile config fields. Flattened into `Config` so an old flat config
+/// file can still be read; `Config::migrate_legacy` moves these into a
+/// profile named "default" on first read, and they're never written back out.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct LegacyFields {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vault_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub daily_notes_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weekly_notes_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monthly_notes_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub templates_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_remote: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_branch: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_exclude_patterns: Option<Vec<String>>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Config {
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub profiles: HashMap<String, Profile>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_profile: Option<String>,
+    #[serde(flatten)]
+    pub legacy: LegacyFields,
+}
+
+impl Config {
+    /// Migrates a flat legacy config (no `profiles` map) into a profile
+    /// named "default", so a config file saved before profiles existed
+    /// keeps working without the user editing JSON by hand. A no-op once
+    /// `profiles` is non-empty.
+    fn migrate_legacy(mut self) -> Self {
+        if self.profiles.is_empty() {
+            if let Some(vault_path) = self.legacy.vault_path.take() {
+                self.profiles.insert(
+                    DEFAULT_PROFILE_NAME.to_string(),
+                    Profile {
+                        vault_path,
+                        daily_notes_path: self.legacy.daily_notes_path.take(),
+                        weekly_notes_path: self.legacy.weekly_notes_path.take(),
+                        monthly_notes_path: self.legacy.monthly_notes_path.take(),
+                        templates_path: self.legacy.templates_path.take(),
+                        git_remote: self.legacy.git_remote.take(),
+                        git_branch: self.legacy.git_branch.take(),
+                        default_exclude_patterns: self.legacy.default_exclude_patterns.take(),
+                        default_frontmatter: None,
+                    },
+                );
+                self.default_profile.get_or_insert_with(|| DEFAULT_PROFILE_NAME.to_string());
+            }
+        }
+        self
+    }
+
+    /// Resolves which profile an invocation should use: `requested` (e.g. a
+    /// `--profile` flag) > the `OBSIDIAN_PROFILE` env var > the config's own
+    /// `default_profile` > "default".
+    fn select_profile_name(&self, requested: Option<&str>) -> String {
+        requested
+            .map(|s| s.to_string())
+            .or_else(|| std::env::var("OBSIDIAN_PROFILE").ok())
+            .or_else(|| self.default_profile.clone())
+            .unwrap_or_else(|| DEFAULT_PROFILE_NAME.to_string())
+    }
 }
 
 /// Returns the full path to the config file.
@@ -27,18 +114,45 @@ pub fn config_path() -> Result<PathBuf> {
     Ok(config_dir.join(CONFIG_DIR).join(CONFIG_FILE))
 }
 
-pub fn load_config() -> Result<Config> {
+/// Loads the config file and returns the selected profile's settings
+/// (see `Config::select_profile_name`), migrating a legacy flat config
+/// into a "default" profile if needed.
+pub fn load_config() -> Result<Profile> {
     let path = config_path()?;
     let content = fs::read_to_string(&path)
         .with_context(|| format!("Config file not found: {}. Run: obsidian-cli set-vault-path /path/to/your/vault", path.display()))?;
     let config: Config = serde_json::from_str(&content)
         .context("Invalid config file format")?;
-    if config.vault_path.is_empty() {
+    let config = config.migrate_legacy();
+
+    let profile_name = config.select_profile_name(None);
+    let profile = config.profiles.get(&profile_name)
+        .cloned()
+        .with_context(|| format!("Profile '{}' not found in config", profile_name))?;
+    if profile.vault_path.is_empty() {
         anyhow::bail!(
             "Vault path not configured.\nRun: obsidian-cli set-vault-path /path/to/your/vault"
         );
     }
-    Ok(config)
+    Ok(profile)
+}
+
+/// Reads the config file (if present), migrating a legacy flat config into
+/// a "default" profile, without requiring any profile to actually exist yet.
+fn load_config_file() -> Option<Config> {
+    let path = config_path().ok()?;
+    let content = fs::read_to_string(&path).ok()?;
+    let config: Config = serde_json::from_str(&content).ok()?;
+    Some(config.migrate_legacy())
+}
+
+/// Saves `profile` under `profile_name`, preserving every other saved
+/// profile, and sets it as the default profile if none is set yet.
+pub fn save_profile(profile_name: &str, profile: Profile) -> Result<()> {
+    let mut config = load_config_file().unwrap_or_default();
+    config.profiles.insert(profile_name.to_string(), profile);
+    config.default_profile.get_or_insert_with(|| profile_name.to_string());
+    save_config(&config)
 }
 
 pub fn save_config(config: &Config) -> Result<()> {
@@ -51,3 +165,108 @@ pub fn save_config(config: &Config) -> Result<()> {
         .with_context(|| format!("Could not write config file: {}", path.display()))?;
     Ok(())
 }
+
+/// Per-field overrides taken from explicit CLI flags. Each `None` falls through
+/// to the next layer in `resolve_config`'s precedence.
+#[derive(Debug, Default)]
+pub struct ConfigOverrides {
+    pub profile: Option<String>,
+    pub vault_path: Option<String>,
+    pub daily_notes_path: Option<String>,
+    pub weekly_notes_path: Option<String>,
+    pub monthly_notes_path: Option<String>,
+    pub templates_path: Option<String>,
+    pub git_remote: Option<String>,
+    pub git_branch: Option<String>,
+    pub default_exclude_patterns: Option<Vec<String>>,
+    pub default_frontmatter: Option<serde_json::Map<String, serde_json::Value>>,
+}
+
+/// The effective configuration after merging all layers.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResolvedConfig {
+    pub vault_path: String,
+    pub daily_notes_path: Option<String>,
+    pub weekly_notes_path: Option<String>,
+    pub monthly_notes_path: Option<String>,
+    pub templates_path: Option<String>,
+    pub git_remote: Option<String>,
+    pub git_branch: Option<String>,
+    pub default_exclude_patterns: Vec<String>,
+    pub default_frontmatter: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Merge configuration sources with precedence: explicit `overrides` (CLI flags)
+/// > environment variables > the saved config file's selected profile (see
+/// `overrides.profile` / `OBSIDIAN_PROFILE` / the config's `default_profile`)
+/// > built-in defaults.
+/// Unlike `load_config`, a missing or absent config file is not an error as
+/// long as the vault path is supplied by an override or the environment.
+pub fn resolve_config(overrides: ConfigOverrides) -> Result<ResolvedConfig> {
+    let file_config = load_config_file();
+    let profile = file_config.as_ref().map(|c| {
+        let name = c.select_profile_name(overrides.profile.as_deref());
+        c.profiles.get(&name).cloned().unwrap_or_default()
+    });
+
+    let vault_path = overrides.vault_path
+        .or_else(|| std::env::var("VAULT_LOCATION").ok())
+        .or_else(|| profile.as_ref().map(|p| p.vault_path.clone()).filter(|v| !v.is_empty()))
+        .context("Vault path not configured. Set --vault-path, VAULT_LOCATION, or run: obsidian-cli set-vault-path /path/to/your/vault")?;
+
+    let daily_notes_path = overrides.daily_notes_path
+        .or_else(|| std::env::var("DAILY_NOTES_PATH").ok())
+        .or_else(|| profile.as_ref().and_then(|p| p.daily_notes_path.clone()));
+
+    let weekly_notes_path = overrides.weekly_notes_path
+        .or_else(|| std::env::var("WEEKLY_NOTES_PATH").ok())
+        .or_else(|| profile.as_ref().and_then(|p| p.weekly_notes_path.clone()));
+
+    let monthly_notes_path = overrides.monthly_notes_path
+        .or_else(|| std::env::var("MONTHLY_NOTES_PATH").ok())
+        .or_else(|| profile.as_ref().and_then(|p| p.monthly_notes_path.clone()));
+
+    let templates_path = overrides.templates_path
+        .or_else(|| std::env::var("TEMPLATES_PATH").ok())
+        .or_else(|| profile.as_ref().and_then(|p| p.templates_path.clone()));
+
+    let git_remote = overrides.git_remote
+        .or_else(|| std::env::var("GIT_REMOTE").ok())
+        .or_else(|| profile.as_ref().and_then(|p| p.git_remote.clone()));
+
+    let git_branch = overrides.git_branch
+        .or_else(|| std::env::var("GIT_BRANCH").ok())
+        .or_else(|| profile.as_ref().and_then(|p| p.git_branch.clone()));
+
+    // Baseline patterns (.obsidian/, .trash/, .mcp_history/) are always
+    // excluded in addition to whatever the user supplies, not replaced by it —
+    // otherwise adding one custom exclusion would re-expose vault internals.
+    let user_exclude_patterns = overrides.default_exclude_patterns
+        .or_else(|| profile.as_ref().and_then(|p| p.default_exclude_patterns.clone()))
+        .unwrap_or_default();
+    let mut default_exclude_patterns: Vec<String> = crate::path_matcher::DEFAULT_EXCLUDE_PATTERNS
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    for pattern in user_exclude_patterns {
+        if !default_exclude_patterns.contains(&pattern) {
+            default_exclude_patterns.push(pattern);
+        }
+    }
+
+    let default_frontmatter = overrides.default_frontmatter
+        .or_else(|| profile.as_ref().and_then(|p| p.default_frontmatter.clone()))
+        .unwrap_or_default();
+
+    Ok(ResolvedConfig {
+        vault_path,
+        daily_notes_path,
+        weekly_notes_path,
+        monthly_notes_path,
+        templates_path,
+        git_remote,
+        git_branch,
+        default_exclude_patterns,
+        default_frontmatter,
+    })
+}
@@ -0,0 +1,224 @@
+// Converts Obsidian-flavored markdown ([[wikilinks]] and ![[embeds]]) into
+// portable standard markdown, for publishing vault notes outside of
+// Obsidian. Wikilink resolution mirrors service.rs's rewrite_wikilinks
+// (same link-matching approach), but against a filename -> path index built
+// once instead of a single renamed note.
+
+use crate::path_matcher::Matcher;
+use rayon::prelude::*;
+use regex::Regex;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Upper bound on `![[embed]]` recursion, to fail safe on an embed cycle.
+const MAX_EMBED_DEPTH: usize = 10;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+pub struct ExportNoteResult {
+    pub path: String,
+    #[schemars(description = "Transformed markdown with wikilinks rewritten and embeds inlined")]
+    pub content: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+pub struct ExportVaultResult {
+    pub success: bool,
+    pub destination: Option<String>,
+    pub files_exported: usize,
+    pub errors: Vec<String>,
+}
+
+/// Maps each note's filename (without extension) to its path relative to
+/// the vault root, built once per export so every wikilink target can be
+/// resolved without re-walking the vault for every note. `matcher` excludes
+/// internal/history paths (e.g. `.obsidian/`, `.mcp_history/`) from both the
+/// index and, via `export_vault`'s own matcher check, the exported output.
+pub fn build_filename_index(vault_root: &Path, matcher: &dyn Matcher) -> HashMap<String, String> {
+    let mut index = HashMap::new();
+    for entry in WalkDir::new(vault_root).into_iter().filter_map(|e| e.ok()) {
+        let entry_path = entry.path();
+        if !entry_path.is_file() || entry_path.extension().and_then(|e| e.to_str()) != Some("md") {
+            continue;
+        }
+        let (Ok(rel_path), Some(stem)) = (
+            entry_path.strip_prefix(vault_root),
+            entry_path.file_stem().and_then(|s| s.to_str()),
+        ) else {
+            continue;
+        };
+        if let Some(rel_str) = rel_path.to_str() {
+            if !matcher.is_match(rel_str) {
+                continue;
+            }
+            index.entry(stem.to_string()).or_insert_with(|| rel_str.to_string());
+        }
+    }
+    index
+}
+
+/// Percent-encodes the characters most likely to break a standard markdown
+/// link target: spaces and parentheses.
+fn percent_encode_path(path: &str) -> String {
+    path.chars()
+        .map(|c| match c {
+            ' ' => "%20".to_string(),
+            '(' => "%28".to_string(),
+            ')' => "%29".to_string(),
+            other => other.to_string(),
+        })
+        .collect()
+}
+
+/// A crude heading-to-anchor slug: lowercase, spaces to dashes. Good enough
+/// for the common case of matching a GitHub/standard-markdown renderer's
+/// auto-generated heading anchors.
+fn slugify_heading(heading: &str) -> String {
+    heading.trim().to_lowercase().replace(' ', "-")
+}
+
+/// A relative link from `from_dir` (relative to `vault_root`) to
+/// `target_rel` (also relative to `vault_root`): walk up out of `from_dir`,
+/// then back down through `target_rel`'s full path.
+fn relative_link(vault_root: &Path, from_dir: &Path, target_rel: &str) -> String {
+    let up = from_dir.strip_prefix(vault_root).map(|p| p.components().count()).unwrap_or(0);
+    let mut parts: Vec<String> = (0..up).map(|_| "..".to_string()).collect();
+    parts.push(target_rel.replace('\\', "/"));
+    parts.join("/")
+}
+
+struct LinkRegexes {
+    wikilink: Regex,
+    target: Regex,
+}
+
+impl LinkRegexes {
+    fn new() -> LinkRegexes {
+        LinkRegexes {
+            wikilink: Regex::new(r"(!?)\[\[([^\]]+)\]\]").unwrap(),
+            target: Regex::new(r"^(?P<file>[^#|]+)(#(?P<section>.+?))?(\|(?P<label>.+?))?$").unwrap(),
+        }
+    }
+}
+
+/// Rewrites `[[target#section|label]]` wikilinks to relative markdown links
+/// and inlines `![[target]]` embeds (recursively resolving the embedded
+/// note's own links), resolving targets against `index`. `from_dir` is the
+/// exporting note's own directory, relative links are computed from there.
+/// `file_tree` tracks the chain of notes currently being embedded, to break
+/// cycles. Unresolvable targets are left as literal text; an embed of a note
+/// already in `file_tree`, or one past `MAX_EMBED_DEPTH`, is replaced by a
+/// warning callout.
+fn resolve_links(
+    content: &str,
+    vault_root: &Path,
+    from_dir: &Path,
+    index: &HashMap<String, String>,
+    regexes: &LinkRegexes,
+    file_tree: &mut Vec<String>,
+) -> String {
+    regexes
+        .wikilink
+        .replace_all(content, |caps: &regex::Captures| {
+            let is_embed = &caps[1] == "!";
+            let inner = caps[2].trim();
+            let Some(target_caps) = regexes.target.captures(inner) else {
+                return caps[0].to_string();
+            };
+            let file = target_caps.name("file").map(|m| m.as_str().trim()).unwrap_or("");
+            let section = target_caps.name("section").map(|m| m.as_str().trim());
+            let label = target_caps.name("label").map(|m| m.as_str().trim());
+
+            let Some(target_rel) = index.get(file) else {
+                return caps[0].to_string();
+            };
+
+            if is_embed {
+                if file_tree.len() >= MAX_EMBED_DEPTH || file_tree.iter().any(|f| f == target_rel) {
+                    return format!("> [!warning] Skipped embed of '{}' (depth limit or cycle)", file);
+                }
+                let target_full = vault_root.join(target_rel);
+                let Ok(embedded) = fs::read_to_string(&target_full) else {
+                    return caps[0].to_string();
+                };
+                file_tree.push(target_rel.clone());
+                let embedded_dir = target_full.parent().unwrap_or(vault_root).to_path_buf();
+                let resolved = resolve_links(&embedded, vault_root, &embedded_dir, index, regexes, file_tree);
+                file_tree.pop();
+                resolved
+            } else {
+                let href = percent_encode_path(&relative_link(vault_root, from_dir, target_rel));
+                let anchor = section.map(|s| format!("#{}", slugify_heading(s))).unwrap_or_default();
+                format!("[{}]({}{})", label.unwrap_or(file), href, anchor)
+            }
+        })
+        .into_owned()
+}
+
+/// Transforms a single note's content into portable standard markdown.
+pub fn export_note_content(
+    content: &str,
+    vault_root: &Path,
+    note_path: &Path,
+    index: &HashMap<String, String>,
+) -> String {
+    let regexes = LinkRegexes::new();
+    let from_dir = note_path.parent().unwrap_or(vault_root).to_path_buf();
+    let mut file_tree = vec![note_path
+        .strip_prefix(vault_root)
+        .ok()
+        .and_then(|p| p.to_str())
+        .unwrap_or("")
+        .to_string()];
+    resolve_links(content, vault_root, &from_dir, index, &regexes, &mut file_tree)
+}
+
+/// Exports every `.md` file under `vault_root` into `destination`,
+/// preserving relative directory structure, processing files in parallel.
+/// `matcher` excludes internal/history paths (e.g. `.obsidian/`,
+/// `.mcp_history/`) from the export, same as `search_vault`/`find_related_notes`.
+pub fn export_vault(vault_root: &Path, destination: &Path, matcher: &dyn Matcher) -> ExportVaultResult {
+    let index = build_filename_index(vault_root, matcher);
+
+    let md_files: Vec<PathBuf> = WalkDir::new(vault_root)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .map(|e| e.into_path())
+        .filter(|p| p.is_file() && p.extension().and_then(|e| e.to_str()) == Some("md"))
+        .filter(|p| {
+            p.strip_prefix(vault_root)
+                .ok()
+                .and_then(|rel| rel.to_str())
+                .is_some_and(|rel_str| matcher.is_match(rel_str))
+        })
+        .collect();
+
+    let results: Vec<Result<(), String>> = md_files
+        .par_iter()
+        .map(|note_path| {
+            let rel_path = note_path
+                .strip_prefix(vault_root)
+                .map_err(|e| format!("{}: {}", note_path.display(), e))?;
+            let content = fs::read_to_string(note_path).map_err(|e| format!("{}: {}", rel_path.display(), e))?;
+            let exported = export_note_content(&content, vault_root, note_path, &index);
+
+            let dest_path = destination.join(rel_path);
+            if let Some(parent) = dest_path.parent() {
+                fs::create_dir_all(parent).map_err(|e| format!("{}: {}", rel_path.display(), e))?;
+            }
+            fs::write(&dest_path, exported).map_err(|e| format!("{}: {}", rel_path.display(), e))
+        })
+        .collect();
+
+    let errors: Vec<String> = results.into_iter().filter_map(|r| r.err()).collect();
+    let files_exported = md_files.len() - errors.len();
+
+    ExportVaultResult {
+        success: errors.is_empty(),
+        destination: Some(destination.display().to_string()),
+        files_exported,
+        errors,
+    }
+}
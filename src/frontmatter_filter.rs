@@ -0,0 +1,170 @@
+// Predicate-based frontmatter filters for faceted search. Lighter-weight
+// than query_notes' general JSONPath queries: a flat list of
+// "field op value" predicates (equality, membership, existence, and
+// date/numeric range comparisons), combined with a single AND/OR mode.
+
+use serde_json::{Map as JsonMap, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CombineMode {
+    And,
+    Or,
+}
+
+impl CombineMode {
+    /// Parses "and"/"or" case-insensitively, defaulting to And for anything else.
+    pub fn parse(raw: Option<&str>) -> CombineMode {
+        match raw.map(|s| s.to_ascii_lowercase()).as_deref() {
+            Some("or") => CombineMode::Or,
+            _ => CombineMode::And,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Op {
+    Eq,
+    Ne,
+    Contains,
+    Exists,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+const OPERATORS: &[(&str, Op)] = &[
+    (">=", Op::Gte),
+    ("<=", Op::Lte),
+    ("!=", Op::Ne),
+    ("=", Op::Eq),
+    (">", Op::Gt),
+    ("<", Op::Lt),
+    ("contains", Op::Contains),
+    ("exists", Op::Exists),
+];
+
+#[derive(Debug)]
+pub struct Predicate {
+    field: String,
+    op: Op,
+    value: Option<String>,
+}
+
+impl Predicate {
+    /// Parses a predicate string such as `status = "done"`, `tags contains work`,
+    /// `created >= 2024-01-01`, `priority > 2`, or `archived exists`. Returns
+    /// `None` if no known operator is found.
+    pub fn parse(raw: &str) -> Option<Predicate> {
+        let tokens: Vec<&str> = raw.split_whitespace().collect();
+        let op_idx = tokens.iter().position(|t| OPERATORS.iter().any(|(sym, _)| sym == t))?;
+        let op_token = tokens[op_idx];
+        let op = *OPERATORS.iter().find(|(sym, _)| *sym == op_token).map(|(_, op)| op)?;
+
+        let field = tokens[..op_idx].join(" ");
+        if field.is_empty() {
+            return None;
+        }
+
+        let value = if matches!(op, Op::Exists) {
+            None
+        } else {
+            let raw_value = tokens[op_idx + 1..].join(" ");
+            if raw_value.is_empty() {
+                return None;
+            }
+            Some(raw_value.trim_matches('"').to_string())
+        };
+
+        Some(Predicate { field, op, value })
+    }
+
+    fn matches(&self, frontmatter: &JsonMap<String, Value>) -> bool {
+        let field_value = frontmatter.get(&self.field);
+        match self.op {
+            Op::Exists => field_value.is_some_and(|v| !v.is_null()),
+            Op::Eq => field_value.is_some_and(|v| value_eq(v, self.value.as_deref().unwrap_or(""))),
+            Op::Ne => !field_value.is_some_and(|v| value_eq(v, self.value.as_deref().unwrap_or(""))),
+            Op::Contains => field_value.is_some_and(|v| value_contains(v, self.value.as_deref().unwrap_or(""))),
+            Op::Gt => compare(field_value, self.value.as_deref().unwrap_or(""), |o| o.is_gt()),
+            Op::Gte => compare(field_value, self.value.as_deref().unwrap_or(""), |o| o.is_ge()),
+            Op::Lt => compare(field_value, self.value.as_deref().unwrap_or(""), |o| o.is_lt()),
+            Op::Lte => compare(field_value, self.value.as_deref().unwrap_or(""), |o| o.is_le()),
+        }
+    }
+}
+
+/// Renders a scalar frontmatter value the way it would be typed in a
+/// predicate or displayed in a facet distribution.
+pub fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn value_eq(value: &Value, target: &str) -> bool {
+    if let (Value::Number(n), Ok(t)) = (value, target.parse::<f64>()) {
+        return n.as_f64().is_some_and(|v| v == t);
+    }
+    value_to_string(value) == target
+}
+
+fn value_contains(value: &Value, target: &str) -> bool {
+    match value {
+        Value::Array(items) => items.iter().any(|v| value_to_string(v) == target),
+        Value::String(s) => s.contains(target),
+        _ => false,
+    }
+}
+
+/// Orders `field_value` against `target`, trying numeric then ISO date
+/// (`YYYY-MM-DD`) comparison before falling back to string ordering.
+fn compare(field_value: Option<&Value>, target: &str, accept: impl Fn(std::cmp::Ordering) -> bool) -> bool {
+    let Some(field_value) = field_value else { return false };
+
+    if let (Some(a), Ok(b)) = (field_value.as_f64(), target.parse::<f64>()) {
+        return a.partial_cmp(&b).is_some_and(&accept);
+    }
+
+    let field_str = value_to_string(field_value);
+    if let (Ok(a), Ok(b)) = (
+        chrono::NaiveDate::parse_from_str(&field_str, "%Y-%m-%d"),
+        chrono::NaiveDate::parse_from_str(target, "%Y-%m-%d"),
+    ) {
+        return accept(a.cmp(&b));
+    }
+
+    accept(field_str.as_str().cmp(target))
+}
+
+/// A flat list of predicates combined with a single AND/OR mode.
+pub struct FilterSet {
+    predicates: Vec<Predicate>,
+    mode: CombineMode,
+}
+
+impl FilterSet {
+    pub fn new(raw_predicates: &[String], mode: CombineMode) -> FilterSet {
+        FilterSet {
+            predicates: raw_predicates.iter().filter_map(|s| Predicate::parse(s)).collect(),
+            mode,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.predicates.is_empty()
+    }
+
+    pub fn matches(&self, frontmatter: &JsonMap<String, Value>) -> bool {
+        if self.predicates.is_empty() {
+            return true;
+        }
+        match self.mode {
+            CombineMode::And => self.predicates.iter().all(|p| p.matches(frontmatter)),
+            CombineMode::Or => self.predicates.iter().any(|p| p.matches(frontmatter)),
+        }
+    }
+}
@@ -0,0 +1,152 @@
+// Thin wrapper around the `git` binary for vault push/pull sync.
+//
+// Shells out rather than linking a git library so a vault directory that
+// is already a git repository can be synced the same way a user would from
+// the command line. No repository is created when `.git` is missing.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+
+const DEFAULT_REMOTE: &str = "origin";
+const DEFAULT_BRANCH: &str = "main";
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct GitSyncResult {
+    pub success: bool,
+    #[schemars(description = "Resulting HEAD commit hash, if the operation reached one")]
+    pub head: Option<String>,
+    #[schemars(description = "Number of files changed by this operation")]
+    pub files_changed: usize,
+    #[schemars(description = "True if a merge conflict occurred (pull only)")]
+    pub conflicts: bool,
+    pub error: Option<String>,
+}
+
+impl GitSyncResult {
+    fn failure(error: impl Into<String>) -> Self {
+        Self {
+            success: false,
+            head: None,
+            files_changed: 0,
+            conflicts: false,
+            error: Some(error.into()),
+        }
+    }
+}
+
+pub struct GitSync<'a> {
+    vault_root: &'a Path,
+    remote: String,
+    branch: String,
+}
+
+impl<'a> GitSync<'a> {
+    pub fn new(vault_root: &'a Path, remote: Option<&str>, branch: Option<&str>) -> Self {
+        Self {
+            vault_root,
+            remote: remote.unwrap_or(DEFAULT_REMOTE).to_string(),
+            branch: branch.unwrap_or(DEFAULT_BRANCH).to_string(),
+        }
+    }
+
+    fn run(&self, args: &[&str]) -> Result<(bool, String, String), String> {
+        let output = Command::new("git")
+            .current_dir(self.vault_root)
+            .args(args)
+            .output()
+            .map_err(|e| format!("Failed to run git {}: {}", args.join(" "), e))?;
+        Ok((
+            output.status.success(),
+            String::from_utf8_lossy(&output.stdout).to_string(),
+            String::from_utf8_lossy(&output.stderr).to_string(),
+        ))
+    }
+
+    fn head(&self) -> Option<String> {
+        let (ok, stdout, _) = self.run(&["rev-parse", "HEAD"]).ok()?;
+        if ok {
+            Some(stdout.trim().to_string())
+        } else {
+            None
+        }
+    }
+
+    /// `git add -A`, commit (auto message if none given), then push.
+    pub fn push(&self, message: Option<String>) -> GitSyncResult {
+        if let Err(e) = self.run(&["add", "-A"]) {
+            return GitSyncResult::failure(e);
+        }
+
+        let staged = match self.run(&["diff", "--cached", "--name-only"]) {
+            Ok((_, stdout, _)) => stdout.lines().filter(|l| !l.is_empty()).count(),
+            Err(e) => return GitSyncResult::failure(e),
+        };
+
+        if staged > 0 {
+            let message = message.unwrap_or_else(|| {
+                format!("Vault sync: {}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S"))
+            });
+            match self.run(&["commit", "-m", &message]) {
+                Ok((true, _, _)) => {}
+                Ok((false, stdout, stderr)) => {
+                    return GitSyncResult::failure(format!("git commit failed: {}{}", stdout, stderr));
+                }
+                Err(e) => return GitSyncResult::failure(e),
+            }
+        }
+
+        match self.run(&["push", &self.remote, &self.branch]) {
+            Ok((true, _, _)) => GitSyncResult {
+                success: true,
+                head: self.head(),
+                files_changed: staged,
+                conflicts: false,
+                error: None,
+            },
+            Ok((false, stdout, stderr)) => GitSyncResult::failure(format!("git push failed: {}{}", stdout, stderr)),
+            Err(e) => GitSyncResult::failure(e),
+        }
+    }
+
+    /// Fetch then merge (fast-forward or merge commit) the configured remote/branch.
+    pub fn pull(&self) -> GitSyncResult {
+        let before_head = self.head();
+
+        if let Err(e) = self.run(&["fetch", &self.remote, &self.branch]) {
+            return GitSyncResult::failure(e);
+        }
+
+        let merge_ref = format!("{}/{}", self.remote, self.branch);
+        let (ok, stdout, stderr) = match self.run(&["merge", "--no-edit", &merge_ref]) {
+            Ok(r) => r,
+            Err(e) => return GitSyncResult::failure(e),
+        };
+
+        let conflicts = stdout.contains("CONFLICT") || stderr.contains("CONFLICT");
+        if !ok && !conflicts {
+            return GitSyncResult::failure(format!("git merge failed: {}{}", stdout, stderr));
+        }
+
+        let after_head = self.head();
+        let files_changed = match (&before_head, &after_head) {
+            (Some(before), Some(after)) if before != after => self
+                .run(&["diff", "--name-only", before, after])
+                .map(|(_, out, _)| out.lines().filter(|l| !l.is_empty()).count())
+                .unwrap_or(0),
+            _ => 0,
+        };
+
+        GitSyncResult {
+            success: !conflicts,
+            head: after_head,
+            files_changed,
+            conflicts,
+            error: if conflicts {
+                Some("Merge produced conflicts that must be resolved manually".to_string())
+            } else {
+                None
+            },
+        }
+    }
+}
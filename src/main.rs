@@ -1,39 +1,79 @@
+mod config;
+mod export;
+mod frontmatter_filter;
+mod git_sync;
+mod note_history;
+mod path_matcher;
+mod postprocessor;
+mod search_index;
 mod service;
+mod template_processor;
+mod vault_dump;
 
 use anyhow::{Context, Result};
-use rmcp::ServiceExt;
+use config::{resolve_config, ConfigOverrides};
+use rmcp::transport::sse_server::SseServer;
 use rmcp::transport::stdio;
+use rmcp::ServiceExt;
 use service::ObsidianService;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let vault_location = std::env::var("VAULT_LOCATION")
-        .context("VAULT_LOCATION environment variable must be set")?;
-    
-    let daily_notes_path = std::env::var("DAILY_NOTES_PATH").ok();
-    let weekly_notes_path = std::env::var("WEEKLY_NOTES_PATH").ok();
-    let monthly_notes_path = std::env::var("MONTHLY_NOTES_PATH").ok();
-    let templates_path = std::env::var("TEMPLATES_PATH").ok();
-
-    let service = ObsidianService::new(
-        &vault_location,
-        daily_notes_path.as_deref(),
-        weekly_notes_path.as_deref(),
-        monthly_notes_path.as_deref(),
-        templates_path.as_deref(),
-    )?;
-
-    let server = service.serve(stdio()).await
-        .map_err(|e| {
-            eprintln!("Error starting server: {:?}", e);
-            e
-        })?;
-    
-    server.waiting().await
-        .map_err(|e| {
-            eprintln!("Error waiting for server: {:?}", e);
-            e
-        })?;
+    let config = resolve_config(ConfigOverrides::default())?;
+    let transport = std::env::var("TRANSPORT").unwrap_or_else(|_| "stdio".to_string());
+
+    if transport.eq_ignore_ascii_case("http") {
+        let bind_addr = std::env::var("BIND_ADDR").unwrap_or_else(|_| "127.0.0.1:8000".to_string());
+        let socket_addr = bind_addr
+            .parse()
+            .with_context(|| format!("Invalid BIND_ADDR: {}", bind_addr))?;
+
+        let ct = SseServer::serve(socket_addr)
+            .await?
+            .with_service(move || {
+                ObsidianService::new(
+                    &config.vault_path,
+                    config.daily_notes_path.as_deref(),
+                    config.weekly_notes_path.as_deref(),
+                    config.monthly_notes_path.as_deref(),
+                    config.templates_path.as_deref(),
+                    config.git_remote.as_deref(),
+                    config.git_branch.as_deref(),
+                    config.default_exclude_patterns.clone(),
+                    config.default_frontmatter.clone(),
+                )
+                .expect("failed to initialize ObsidianService")
+            });
+
+        eprintln!("MCP server listening over HTTP/SSE on {}", bind_addr);
+
+        tokio::signal::ctrl_c().await?;
+        ct.cancel();
+    } else {
+        let service = ObsidianService::new(
+            &config.vault_path,
+            config.daily_notes_path.as_deref(),
+            config.weekly_notes_path.as_deref(),
+            config.monthly_notes_path.as_deref(),
+            config.templates_path.as_deref(),
+            config.git_remote.as_deref(),
+            config.git_branch.as_deref(),
+            config.default_exclude_patterns.clone(),
+            config.default_frontmatter.clone(),
+        )?;
+
+        let server = service.serve(stdio()).await
+            .map_err(|e| {
+                eprintln!("Error starting server: {:?}", e);
+                e
+            })?;
+
+        server.waiting().await
+            .map_err(|e| {
+                eprintln!("Error waiting for server: {:?}", e);
+                e
+            })?;
+    }
 
     Ok(())
 }
@@ -0,0 +1,90 @@
+// Atomic writes and per-note version history.
+//
+// Every overwrite or delete snapshots the prior content into a hidden
+// `.mcp_history/<relpath>/<timestamp>.md` store before touching the file,
+// and all writes go through a temp-file-then-rename so a crash never
+// leaves a half-written note on disk.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const HISTORY_DIR: &str = ".mcp_history";
+
+fn history_dir(vault_root: &Path, rel_path: &str) -> PathBuf {
+    vault_root.join(HISTORY_DIR).join(rel_path)
+}
+
+fn timestamp_now() -> String {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis().to_string())
+        .unwrap_or_else(|_| "0".to_string())
+}
+
+/// Write `content` to `full_path` atomically: write to a temp file in the
+/// same directory, then rename over the target, so a crash never leaves a
+/// half-written note.
+pub fn atomic_write(full_path: &Path, content: &str) -> io::Result<()> {
+    let dir = full_path.parent().unwrap_or_else(|| Path::new("."));
+    let file_name = full_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("note");
+    let tmp_path = dir.join(format!(".{}.tmp-{}", file_name, timestamp_now()));
+    fs::write(&tmp_path, content)?;
+    fs::rename(&tmp_path, full_path)
+}
+
+/// Snapshot the current on-disk content of `full_path` (if it exists) into
+/// `.mcp_history/<rel_path>/<timestamp>.md` before it's overwritten or deleted.
+pub fn snapshot(vault_root: &Path, rel_path: &str, full_path: &Path) -> Option<String> {
+    if !full_path.is_file() {
+        return None;
+    }
+    let content = fs::read(full_path).ok()?;
+    let dir = history_dir(vault_root, rel_path);
+    fs::create_dir_all(&dir).ok()?;
+    let timestamp = timestamp_now();
+    fs::write(dir.join(format!("{}.md", timestamp)), content).ok()?;
+    Some(timestamp)
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct HistoryVersion {
+    pub timestamp: String,
+    pub size: u64,
+}
+
+/// List saved versions of `rel_path`, oldest first.
+pub fn list_history(vault_root: &Path, rel_path: &str) -> Vec<HistoryVersion> {
+    let dir = history_dir(vault_root, rel_path);
+    let mut versions: Vec<HistoryVersion> = fs::read_dir(&dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            let timestamp = path.file_stem()?.to_str()?.to_string();
+            let size = entry.metadata().ok()?.len();
+            Some(HistoryVersion { timestamp, size })
+        })
+        .collect();
+    versions.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+    versions
+}
+
+/// Atomically restore `rel_path` to the content saved under `timestamp`.
+pub fn restore_version(
+    vault_root: &Path,
+    rel_path: &str,
+    full_path: &Path,
+    timestamp: &str,
+) -> Result<(), String> {
+    let snapshot_path = history_dir(vault_root, rel_path).join(format!("{}.md", timestamp));
+    let content = fs::read_to_string(&snapshot_path)
+        .map_err(|e| format!("Version {} not found for {}: {}", timestamp, rel_path, e))?;
+    atomic_write(full_path, &content).map_err(|e| format!("Failed to restore version: {}", e))
+}
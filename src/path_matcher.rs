@@ -0,0 +1,160 @@
+// Composable include/exclude path matchers for vault traversal, modeled on
+// git's sparse-checkout narrowing: an include set builds an `IncludeMatcher`
+// (or falls back to `AlwaysMatcher` when empty), an exclude set builds
+// another, and the two are combined with a `DifferenceMatcher` so a path is
+// in scope only when included and not excluded.
+//
+// Patterns come in three flavors, parsed by prefix:
+//   - `path:<prefix>`        matches any path under that directory
+//   - `rootfilesin:<dir>`    matches only files directly inside that
+//                            directory, not its subdirectories
+//   - anything else          a glob (`*`, `**`, `?`) matched against the
+//                            whole relative path
+
+use regex::Regex;
+
+/// Default exclude patterns applied even when the caller supplies none, so
+/// internal/archived notes stay out of search and related-notes results.
+pub const DEFAULT_EXCLUDE_PATTERNS: &[&str] = &["path:.obsidian/", "path:.trash/", "path:.mcp_history/"];
+
+pub trait Matcher {
+    fn is_match(&self, rel_path: &str) -> bool;
+}
+
+pub struct AlwaysMatcher;
+
+impl Matcher for AlwaysMatcher {
+    fn is_match(&self, _rel_path: &str) -> bool {
+        true
+    }
+}
+
+pub struct NeverMatcher;
+
+impl Matcher for NeverMatcher {
+    fn is_match(&self, _rel_path: &str) -> bool {
+        false
+    }
+}
+
+enum Pattern {
+    Path(String),
+    RootFilesIn(String),
+    Glob(Regex),
+}
+
+impl Pattern {
+    fn parse(raw: &str) -> Pattern {
+        if let Some(prefix) = raw.strip_prefix("path:") {
+            Pattern::Path(normalize_dir(prefix))
+        } else if let Some(dir) = raw.strip_prefix("rootfilesin:") {
+            Pattern::RootFilesIn(normalize_dir(dir))
+        } else {
+            Pattern::Glob(glob_to_regex(raw))
+        }
+    }
+
+    fn is_match(&self, rel_path: &str) -> bool {
+        match self {
+            Pattern::Path(prefix) => rel_path.starts_with(prefix.as_str()),
+            Pattern::RootFilesIn(dir) => {
+                match rel_path.rsplit_once('/') {
+                    Some((parent, _file)) => parent == dir.trim_end_matches('/'),
+                    None => dir.is_empty(),
+                }
+            }
+            Pattern::Glob(re) => re.is_match(rel_path),
+        }
+    }
+}
+
+fn normalize_dir(dir: &str) -> String {
+    let trimmed = dir.trim_matches('/');
+    if trimmed.is_empty() {
+        String::new()
+    } else {
+        format!("{}/", trimmed)
+    }
+}
+
+/// Translate a `*`/`**`/`?` glob into an anchored regex matched against a
+/// forward-slash-separated relative path. `**` crosses directory boundaries,
+/// a lone `*` does not, and other regex metacharacters are escaped literally.
+fn glob_to_regex(glob: &str) -> Regex {
+    let mut pattern = String::from("^");
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => {
+                if chars.peek() == Some(&'*') {
+                    chars.next();
+                    pattern.push_str(".*");
+                } else {
+                    pattern.push_str("[^/]*");
+                }
+            }
+            '?' => pattern.push_str("[^/]"),
+            _ => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    pattern.push('$');
+    Regex::new(&pattern).unwrap_or_else(|_| Regex::new("$^").expect("empty-match fallback regex"))
+}
+
+/// Matches any path selected by at least one of its patterns; matches
+/// nothing when constructed from an empty pattern list.
+struct IncludeMatcher {
+    patterns: Vec<Pattern>,
+}
+
+impl IncludeMatcher {
+    fn new(raw_patterns: &[String]) -> IncludeMatcher {
+        IncludeMatcher {
+            patterns: raw_patterns.iter().map(|p| Pattern::parse(p)).collect(),
+        }
+    }
+}
+
+impl Matcher for IncludeMatcher {
+    fn is_match(&self, rel_path: &str) -> bool {
+        self.patterns.iter().any(|p| p.is_match(rel_path))
+    }
+}
+
+/// Matches paths accepted by `base` but not by `exclude`.
+struct DifferenceMatcher {
+    base: Box<dyn Matcher>,
+    exclude: Box<dyn Matcher>,
+}
+
+impl Matcher for DifferenceMatcher {
+    fn is_match(&self, rel_path: &str) -> bool {
+        self.base.is_match(rel_path) && !self.exclude.is_match(rel_path)
+    }
+}
+
+/// Build the effective matcher for a traversal: `include` patterns select
+/// the base scope (the whole vault when empty), and `exclude` patterns
+/// (combined with `extra_default_excludes`, e.g. from config) are then
+/// subtracted from it.
+pub fn build_matcher(
+    include: &[String],
+    exclude: &[String],
+    extra_default_excludes: &[String],
+) -> Box<dyn Matcher> {
+    let base: Box<dyn Matcher> = if include.is_empty() {
+        Box::new(AlwaysMatcher)
+    } else {
+        Box::new(IncludeMatcher::new(include))
+    };
+
+    let mut exclude_patterns = exclude.to_vec();
+    exclude_patterns.extend(extra_default_excludes.iter().cloned());
+    let exclude_matcher: Box<dyn Matcher> = if exclude_patterns.is_empty() {
+        Box::new(NeverMatcher)
+    } else {
+        Box::new(IncludeMatcher::new(&exclude_patterns))
+    };
+
+    Box::new(DifferenceMatcher { base, exclude: exclude_matcher })
+}
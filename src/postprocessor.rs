@@ -0,0 +1,87 @@
+// A postprocessing pipeline run over a note's body immediately before it is
+// written, modeled on obsidian-export's postprocessor chain: each stage can
+// rewrite the body, mutate context (destination path, frontmatter), stop the
+// chain early and write as-is, or abandon the write entirely.
+
+use serde_json::{Map as JsonMap, Value};
+
+/// Mutable context threaded through a postprocessor chain alongside the body.
+pub struct NoteContext {
+    pub path: String,
+    pub frontmatter: Option<JsonMap<String, Value>>,
+}
+
+/// What a postprocessor wants the chain to do next.
+pub enum PostprocessorResult {
+    /// Run the next postprocessor in the chain.
+    Continue,
+    /// Stop running postprocessors and write the note as it stands.
+    StopAndWrite,
+    /// Abandon the write entirely; nothing is written to disk.
+    Skip,
+}
+
+/// A single stage in the postprocessing pipeline.
+pub trait Postprocessor: Send + Sync {
+    fn process(&self, ctx: &mut NoteContext, body: &mut String) -> PostprocessorResult;
+}
+
+/// Converts `\r\n`/`\r` line endings to `\n`.
+pub struct NormalizeLineEndings;
+
+impl Postprocessor for NormalizeLineEndings {
+    fn process(&self, _ctx: &mut NoteContext, body: &mut String) -> PostprocessorResult {
+        if body.contains('\r') {
+            *body = body.replace("\r\n", "\n").replace('\r', "\n");
+        }
+        PostprocessorResult::Continue
+    }
+}
+
+/// Ensures the body ends with a trailing newline, appending one if it is
+/// missing; trailing newlines already present are left as they are.
+pub struct EnsureTrailingNewline;
+
+impl Postprocessor for EnsureTrailingNewline {
+    fn process(&self, _ctx: &mut NoteContext, body: &mut String) -> PostprocessorResult {
+        if !body.ends_with('\n') {
+            body.push('\n');
+        }
+        PostprocessorResult::Continue
+    }
+}
+
+/// Merges a fixed set of default frontmatter keys into `ctx.frontmatter`,
+/// without overwriting keys the note (or an earlier stage) already set.
+pub struct MergeFrontmatter {
+    defaults: JsonMap<String, Value>,
+}
+
+impl MergeFrontmatter {
+    pub fn new(defaults: JsonMap<String, Value>) -> Self {
+        MergeFrontmatter { defaults }
+    }
+}
+
+impl Postprocessor for MergeFrontmatter {
+    fn process(&self, ctx: &mut NoteContext, _body: &mut String) -> PostprocessorResult {
+        let fm = ctx.frontmatter.get_or_insert_with(JsonMap::new);
+        for (key, value) in &self.defaults {
+            fm.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+        PostprocessorResult::Continue
+    }
+}
+
+/// Runs `pipeline` over `body`/`ctx` in order, honoring early-exit results.
+/// Returns `true` if the note should be written, `false` if it was skipped.
+pub fn run_pipeline(pipeline: &[Box<dyn Postprocessor>], ctx: &mut NoteContext, body: &mut String) -> bool {
+    for stage in pipeline {
+        match stage.process(ctx, body) {
+            PostprocessorResult::Continue => {}
+            PostprocessorResult::StopAndWrite => return true,
+            PostprocessorResult::Skip => return false,
+        }
+    }
+    true
+}
@@ -0,0 +1,558 @@
+// Inverted-index subsystem backing ranked, typo-tolerant full-text search
+// and backlink lookups.
+//
+// The index maps lowercased term -> posting list (path, term frequency),
+// tag -> notes, and note -> outbound links (plus the derived reverse
+// note -> backlinks map), alongside per-document length and mtime so it
+// can be refreshed incrementally instead of re-walking the whole vault on
+// every query.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+use regex::Regex;
+use walkdir::WalkDir;
+
+const INDEX_FILE: &str = ".mcp_search_index.json";
+const K1: f64 = 1.2;
+const B: f64 = 0.75;
+/// Weight applied to a title token's term frequency relative to a body token.
+const TITLE_FIELD_BOOST: usize = 3;
+/// Weight applied to a tag token's term frequency relative to a body token.
+const TAG_FIELD_BOOST: usize = 2;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Posting {
+    pub path: String,
+    pub term_frequency: usize,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SearchIndex {
+    /// term -> postings
+    postings: HashMap<String, Vec<Posting>>,
+    /// note path -> token count
+    doc_lengths: HashMap<String, usize>,
+    /// note path -> last-indexed mtime (unix seconds)
+    doc_mtimes: HashMap<String, u64>,
+    /// note path -> frontmatter tags
+    tags: HashMap<String, Vec<String>>,
+    /// tag -> note paths (inverse of `tags`)
+    tag_index: HashMap<String, Vec<String>>,
+    /// note path -> raw wikilink targets found in its body
+    links: HashMap<String, Vec<String>>,
+    /// note path -> note paths whose links resolve to it (derived from `links`)
+    backlinks: HashMap<String, Vec<String>>,
+}
+
+pub struct ScoredDoc {
+    pub path: String,
+    pub score: f64,
+    /// (query_term, matched_index_term) pairs where fuzzy matching
+    /// substituted a different term than the one typed, e.g. typo
+    /// tolerance or prefix completion. Empty for exact matches.
+    pub matched_terms: Vec<(String, String)>,
+}
+
+/// Split text into lowercased alphanumeric tokens.
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_lowercase())
+        .collect()
+}
+
+/// Standard Levenshtein edit distance.
+/// Levenshtein edit distance, bounded by `max_dist`: returns `None` as soon
+/// as it's certain the true distance exceeds the bound, instead of
+/// computing the full matrix. Each DP row tracks its running minimum so a
+/// row that can no longer produce a result within `max_dist` short-circuits
+/// the rest of the comparison.
+pub fn levenshtein_within(a: &str, b: &str, max_dist: usize) -> Option<usize> {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    if a.len().abs_diff(b.len()) > max_dist {
+        return None;
+    }
+
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for i in 1..=a.len() {
+        let mut prev = row[0];
+        row[0] = i;
+        let mut row_min = row[0];
+        for j in 1..=b.len() {
+            let tmp = row[j];
+            row[j] = if a[i - 1] == b[j - 1] {
+                prev
+            } else {
+                1 + prev.min(row[j]).min(row[j - 1])
+            };
+            prev = tmp;
+            row_min = row_min.min(row[j]);
+        }
+        if row_min > max_dist {
+            return None;
+        }
+    }
+
+    let distance = row[b.len()];
+    if distance <= max_dist {
+        Some(distance)
+    } else {
+        None
+    }
+}
+
+fn file_mtime_secs(path: &Path) -> Option<u64> {
+    let metadata = std::fs::metadata(path).ok()?;
+    let modified = metadata.modified().ok()?;
+    modified.duration_since(UNIX_EPOCH).ok().map(|d| d.as_secs())
+}
+
+impl SearchIndex {
+    fn index_file(vault_root: &Path) -> std::path::PathBuf {
+        vault_root.join(INDEX_FILE)
+    }
+
+    /// Load the persisted index (or start empty) and incrementally refresh
+    /// it against the current state of `.md` files under `vault_root`.
+    pub fn load_and_refresh(vault_root: &Path) -> Self {
+        let mut index = Self::load(vault_root).unwrap_or_default();
+        index.refresh(vault_root);
+        index
+    }
+
+    /// Force a full rescan, discarding any previous index state.
+    pub fn rebuild(vault_root: &Path) -> Self {
+        let mut index = Self::default();
+        index.refresh(vault_root);
+        index
+    }
+
+    fn load(vault_root: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(Self::index_file(vault_root)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    pub fn save(&self, vault_root: &Path) {
+        if let Ok(content) = serde_json::to_string(self) {
+            let _ = std::fs::write(Self::index_file(vault_root), content);
+        }
+    }
+
+    /// Re-parse any note whose mtime has changed since it was last
+    /// indexed, and drop entries for notes that no longer exist. A change
+    /// in size alone, with the mtime unchanged, is not detected.
+    fn refresh(&mut self, vault_root: &Path) {
+        let mut seen = std::collections::HashSet::new();
+
+        for entry in WalkDir::new(vault_root)
+            .into_iter()
+            .filter_entry(|e| e.file_name() != INDEX_FILE)
+            .filter_map(|e| e.ok())
+        {
+            let path = entry.path();
+            if !path.is_file() || path.extension().and_then(|s| s.to_str()) != Some("md") {
+                continue;
+            }
+            let Ok(rel_path) = path.strip_prefix(vault_root) else {
+                continue;
+            };
+            let rel_path = rel_path.to_string_lossy().to_string();
+            let Some(mtime) = file_mtime_secs(path) else {
+                continue;
+            };
+
+            seen.insert(rel_path.clone());
+
+            if self.doc_mtimes.get(&rel_path) == Some(&mtime) {
+                continue;
+            }
+
+            if let Ok(content) = std::fs::read_to_string(path) {
+                self.index_document(&rel_path, &content, mtime);
+            }
+        }
+
+        let stale: Vec<String> = self
+            .doc_mtimes
+            .keys()
+            .filter(|p| !seen.contains(*p))
+            .cloned()
+            .collect();
+        for path in stale {
+            self.remove_document(&path);
+        }
+
+        self.recompute_backlinks();
+        self.save(vault_root);
+    }
+
+    fn remove_document(&mut self, path: &str) {
+        self.doc_mtimes.remove(path);
+        self.doc_lengths.remove(path);
+        for postings in self.postings.values_mut() {
+            postings.retain(|p| p.path != path);
+        }
+        self.postings.retain(|_, postings| !postings.is_empty());
+
+        if let Some(tags) = self.tags.remove(path) {
+            for tag in tags {
+                if let Some(paths) = self.tag_index.get_mut(&tag) {
+                    paths.retain(|p| p != path);
+                }
+            }
+            self.tag_index.retain(|_, paths| !paths.is_empty());
+        }
+        self.links.remove(path);
+    }
+
+    fn index_document(&mut self, path: &str, content: &str, mtime: u64) {
+        self.remove_document(path);
+
+        // Title and tag tokens are folded into the same postings at a
+        // boosted weight, so a short note whose title or tags match the
+        // query can outrank a long note that only mentions it in passing.
+        let body_tokens = tokenize(content);
+        let title = Path::new(path).file_stem().and_then(|s| s.to_str()).unwrap_or("");
+        let title_tokens = tokenize(title);
+        let tags = Self::extract_frontmatter_tags(content);
+        let tag_tokens: Vec<String> = tags.iter().flat_map(|tag| tokenize(tag)).collect();
+
+        let doc_length = body_tokens.len()
+            + title_tokens.len() * TITLE_FIELD_BOOST
+            + tag_tokens.len() * TAG_FIELD_BOOST;
+        self.doc_lengths.insert(path.to_string(), doc_length);
+        self.doc_mtimes.insert(path.to_string(), mtime);
+
+        let mut term_freq: HashMap<String, usize> = HashMap::new();
+        for token in body_tokens {
+            *term_freq.entry(token).or_insert(0) += 1;
+        }
+        for token in title_tokens {
+            *term_freq.entry(token).or_insert(0) += TITLE_FIELD_BOOST;
+        }
+        for token in tag_tokens {
+            *term_freq.entry(token).or_insert(0) += TAG_FIELD_BOOST;
+        }
+
+        for (term, tf) in term_freq {
+            self.postings.entry(term).or_default().push(Posting {
+                path: path.to_string(),
+                term_frequency: tf,
+            });
+        }
+
+        if !tags.is_empty() {
+            for tag in &tags {
+                self.tag_index.entry(tag.clone()).or_default().push(path.to_string());
+            }
+            self.tags.insert(path.to_string(), tags);
+        }
+
+        let links = Self::extract_links(content);
+        if !links.is_empty() {
+            self.links.insert(path.to_string(), links);
+        }
+    }
+
+    /// Tags declared in `content`'s YAML frontmatter, if any.
+    fn extract_frontmatter_tags(content: &str) -> Vec<String> {
+        if !content.starts_with("---\n") {
+            return Vec::new();
+        }
+        let Some(end_pos) = content[4..].find("\n---\n") else {
+            return Vec::new();
+        };
+        let yaml_str = &content[4..end_pos + 4];
+        serde_yaml::from_str::<serde_yaml::Value>(yaml_str)
+            .ok()
+            .and_then(|value| value.get("tags").cloned())
+            .and_then(|tags| serde_yaml::from_value::<Vec<String>>(tags).ok())
+            .unwrap_or_default()
+    }
+
+    /// Raw `[[target]]` wikilink targets referenced in `content`.
+    fn extract_links(content: &str) -> Vec<String> {
+        let link_regex = Regex::new(r"\[\[([^\]|#]+)").unwrap();
+        link_regex
+            .captures_iter(content)
+            .filter_map(|cap| cap.get(1).map(|m| m.as_str().trim().to_string()))
+            .collect()
+    }
+
+    /// A note's outbound link target is considered resolved to any note
+    /// whose relative path (with or without a trailing `.md`) or bare file
+    /// stem exactly equals it, so a link to "Go" matches "Go.md" but not
+    /// "Congo.md" or "Django.md".
+    fn resolves_to(candidate: &str, link: &str) -> bool {
+        let link = link.trim_end_matches(".md");
+        let candidate_no_ext = candidate.strip_suffix(".md").unwrap_or(candidate);
+        if candidate_no_ext == link {
+            return true;
+        }
+        Path::new(candidate)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .map(|stem| stem == link)
+            .unwrap_or(false)
+    }
+
+    /// Rebuild the reverse `note -> backlinks` map from `links` against the
+    /// current document set. Cheap enough to redo in full on every refresh,
+    /// which avoids backlinks ever drifting stale relative to `links`.
+    fn recompute_backlinks(&mut self) {
+        self.backlinks.clear();
+        for (source, targets) in &self.links {
+            for target in targets {
+                for candidate in self.doc_lengths.keys() {
+                    if candidate == source {
+                        continue;
+                    }
+                    if Self::resolves_to(candidate, target) {
+                        self.backlinks.entry(candidate.clone()).or_default().push(source.clone());
+                    }
+                }
+            }
+        }
+        for sources in self.backlinks.values_mut() {
+            sources.sort();
+            sources.dedup();
+        }
+    }
+
+    fn doc_count(&self) -> usize {
+        self.doc_lengths.len()
+    }
+
+    fn avg_doc_len(&self) -> f64 {
+        let n = self.doc_count();
+        if n == 0 {
+            return 0.0;
+        }
+        self.doc_lengths.values().sum::<usize>() as f64 / n as f64
+    }
+
+    pub fn document_count(&self) -> usize {
+        self.doc_count()
+    }
+
+    pub fn term_count(&self) -> usize {
+        self.postings.len()
+    }
+
+    /// Every note path currently tracked by the index.
+    pub fn note_paths(&self) -> Vec<String> {
+        self.doc_lengths.keys().cloned().collect()
+    }
+
+    /// Frontmatter tags recorded for `path`.
+    pub fn tags_for(&self, path: &str) -> Vec<String> {
+        self.tags.get(path).cloned().unwrap_or_default()
+    }
+
+    /// Note paths whose frontmatter includes `tag`.
+    pub fn notes_with_tag(&self, tag: &str) -> Vec<String> {
+        self.tag_index.get(tag).cloned().unwrap_or_default()
+    }
+
+    /// Raw wikilink targets found in `path`'s body.
+    pub fn outbound_links(&self, path: &str) -> Vec<String> {
+        self.links.get(path).cloned().unwrap_or_default()
+    }
+
+    /// Note paths that link to `path`.
+    pub fn backlinks(&self, path: &str) -> Vec<String> {
+        self.backlinks.get(path).cloned().unwrap_or_default()
+    }
+
+    /// Terms within edit-distance tolerance of `term` (tolerance scales with
+    /// term length: 0 for terms of 4 chars or fewer, 1 for 5-8, 2 beyond
+    /// that). When `as_prefix` is set (used for the last word of a query,
+    /// which may be a partially-typed word), a candidate also matches when
+    /// some prefix of it — not just the whole word — is within tolerance.
+    fn fuzzy_matches(&self, term: &str, as_prefix: bool) -> Vec<&str> {
+        let len = term.chars().count();
+        let tolerance = if len <= 4 {
+            0
+        } else if len <= 8 {
+            1
+        } else {
+            2
+        };
+        self.postings
+            .keys()
+            .filter(|candidate| {
+                if levenshtein_within(term, candidate, tolerance).is_some() {
+                    return true;
+                }
+                if !as_prefix {
+                    return false;
+                }
+                let chars: Vec<char> = candidate.chars().collect();
+                if chars.len() <= len {
+                    return false;
+                }
+                let lo = len.saturating_sub(tolerance).max(1);
+                let hi = (len + tolerance).min(chars.len() - 1);
+                (lo..=hi).any(|prefix_len| {
+                    let prefix: String = chars[..prefix_len].iter().collect();
+                    levenshtein_within(term, &prefix, tolerance).is_some()
+                })
+            })
+            .map(|s| s.as_str())
+            .collect()
+    }
+
+    /// Score every eligible document against the query terms with BM25,
+    /// returning results sorted by descending score.
+    pub fn search(
+        &self,
+        query_terms: &[String],
+        fuzzy: bool,
+        eligible: impl Fn(&str) -> bool,
+    ) -> Vec<ScoredDoc> {
+        let n = self.doc_count();
+        if n == 0 {
+            return Vec::new();
+        }
+        let avgdl = self.avg_doc_len();
+        let mut scores: HashMap<String, f64> = HashMap::new();
+        let mut matched_by_path: HashMap<String, Vec<(String, String)>> = HashMap::new();
+        let last_idx = query_terms.len().saturating_sub(1);
+
+        for (i, query_term) in query_terms.iter().enumerate() {
+            // Postings contributing to this query term: the exact term plus,
+            // when fuzzy is enabled, any index term within edit distance (the
+            // final query word is additionally matched as a prefix).
+            let matched_terms: Vec<String> = if fuzzy {
+                self.fuzzy_matches(query_term, i == last_idx)
+                    .into_iter()
+                    .map(|s| s.to_string())
+                    .collect()
+            } else if self.postings.contains_key(query_term) {
+                vec![query_term.clone()]
+            } else {
+                Vec::new()
+            };
+
+            if matched_terms.is_empty() {
+                continue;
+            }
+
+            // Merge postings from all matched variants, attributing them to
+            // the original query term for df/tf purposes.
+            let mut merged: HashMap<String, usize> = HashMap::new();
+            for term in &matched_terms {
+                if let Some(postings) = self.postings.get(term) {
+                    for posting in postings {
+                        *merged.entry(posting.path.clone()).or_insert(0) += posting.term_frequency;
+                    }
+                }
+            }
+
+            let df = merged.len();
+            if df == 0 {
+                continue;
+            }
+            let idf = ((n as f64 - df as f64 + 0.5) / (df as f64 + 0.5) + 1.0).ln();
+
+            for (path, tf) in merged {
+                if !eligible(&path) {
+                    continue;
+                }
+                let dl = *self.doc_lengths.get(&path).unwrap_or(&0) as f64;
+                let denom = tf as f64 + K1 * (1.0 - B + B * dl / avgdl.max(1.0));
+                let contribution = idf * (tf as f64 * (K1 + 1.0)) / denom.max(f64::EPSILON);
+                *scores.entry(path.clone()).or_insert(0.0) += contribution;
+
+                for term in &matched_terms {
+                    let in_doc = self
+                        .postings
+                        .get(term)
+                        .is_some_and(|postings| postings.iter().any(|p| p.path == path));
+                    if term != query_term && in_doc {
+                        let pair = (query_term.clone(), term.clone());
+                        let entry = matched_by_path.entry(path.clone()).or_default();
+                        if !entry.contains(&pair) {
+                            entry.push(pair);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut results: Vec<ScoredDoc> = scores
+            .into_iter()
+            .map(|(path, score)| {
+                let matched_terms = matched_by_path.remove(&path).unwrap_or_default();
+                ScoredDoc { path, score, matched_terms }
+            })
+            .collect();
+        results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index_with(docs: &[(&str, &str)]) -> SearchIndex {
+        let mut index = SearchIndex::default();
+        for (path, body) in docs {
+            let tokens = tokenize(body);
+            let mut tf: HashMap<String, usize> = HashMap::new();
+            for token in &tokens {
+                *tf.entry(token.clone()).or_insert(0) += 1;
+            }
+            for (term, count) in tf {
+                index.postings.entry(term).or_default().push(Posting {
+                    path: path.to_string(),
+                    term_frequency: count,
+                });
+            }
+            index.doc_lengths.insert(path.to_string(), tokens.len());
+        }
+        index
+    }
+
+    #[test]
+    fn test_bm25_ranks_higher_term_frequency_above_lower() {
+        let index = index_with(&[
+            ("a.md", "rust rust rust programming"),
+            ("b.md", "rust programming language"),
+        ]);
+        let results = index.search(&["rust".to_string()], false, |_| true);
+        assert_eq!(results[0].path, "a.md");
+        assert!(results[0].score > results[1].score);
+    }
+
+    #[test]
+    fn test_bm25_no_match_returns_empty() {
+        let index = index_with(&[("a.md", "rust programming")]);
+        let results = index.search(&["python".to_string()], false, |_| true);
+        assert!(results.is_empty());
+    }
+
+    #[test]
+    fn test_bm25_respects_eligible_filter() {
+        let index = index_with(&[
+            ("a.md", "rust programming"),
+            ("b.md", "rust programming"),
+        ]);
+        let results = index.search(&["rust".to_string()], false, |p| p != "a.md");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].path, "b.md");
+    }
+
+    #[test]
+    fn test_resolves_to_exact_stem_not_substring() {
+        assert!(SearchIndex::resolves_to("Go.md", "Go"));
+        assert!(!SearchIndex::resolves_to("Congo.md", "Go"));
+        assert!(!SearchIndex::resolves_to("Django.md", "Go"));
+    }
+}
@@ -5,20 +5,43 @@ use rmcp::{
     schemars, tool, tool_handler, tool_router, ServerHandler,
 };
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::collections::HashMap;
 use serde_json::Map as JsonMap;
 use chrono::{Local, NaiveDate};
 use regex::Regex;
 use walkdir::WalkDir;
+use uuid::Uuid;
 use std::fs;
 
+use crate::export;
+use crate::frontmatter_filter::{self, CombineMode, FilterSet};
+use crate::git_sync::{GitSync, GitSyncResult};
+use crate::note_history::{self, HistoryVersion};
+use crate::path_matcher;
+use crate::postprocessor::{self, Postprocessor};
+use crate::search_index::{self, SearchIndex};
+use crate::template_processor::TemplateProcessor;
+use crate::vault_dump::{self, DumpResult, LoadResult};
+
+/// Upper bound on the span accepted by `get_daily_notes_range`, to avoid
+/// runaway iteration over an accidentally huge or reversed date range.
+const MAX_DAILY_NOTE_RANGE_DAYS: i64 = 366;
+
+/// Upper bound on `{{include: ...}}` recursion depth, to fail fast on a
+/// template that (directly or indirectly) includes itself.
+const MAX_TEMPLATE_INCLUDE_DEPTH: usize = 10;
+
 pub struct ObsidianService {
     vault_root: PathBuf,
     daily_notes_path: Option<PathBuf>,
     weekly_notes_path: Option<PathBuf>,
     monthly_notes_path: Option<PathBuf>,
     templates_path: Option<PathBuf>,
+    git_remote: Option<String>,
+    git_branch: Option<String>,
+    default_exclude_patterns: Vec<String>,
+    postprocessors: Vec<Box<dyn Postprocessor>>,
     tool_router: ToolRouter<Self>,
 }
 
@@ -72,6 +95,8 @@ pub struct CreateOrUpdateNoteRequest {
     pub frontmatter: Option<JsonMap<String, serde_json::Value>>,
     #[schemars(description = "Update mode: 'overwrite' (default) - replaces entire file, 'append' - adds content after existing body, 'prepend' - adds content before existing body")]
     pub mode: Option<String>,
+    #[schemars(description = "If true, skip snapshotting the previous content to note history before overwriting. Default: false. Useful to avoid history bloat during bulk operations")]
+    pub skip_history: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
@@ -80,20 +105,163 @@ pub struct GetDailyNoteRequest {
     pub date: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct GetDailyNotesRangeRequest {
+    #[schemars(description = "Start date (inclusive): 'today', 'yesterday', 'tomorrow', or 'YYYY-MM-DD' format")]
+    pub from: String,
+    #[schemars(description = "End date (inclusive): 'today', 'yesterday', 'tomorrow', or 'YYYY-MM-DD' format")]
+    pub to: String,
+    #[schemars(description = "Also concatenate each found day's body into a single rollup string with '## YYYY-MM-DD' headers. Default: false")]
+    pub rollup: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct DailyNoteEntry {
+    pub date: String,
+    pub path: String,
+    pub content: String,
+    #[schemars(description = "YAML frontmatter metadata")]
+    pub frontmatter: Option<JsonMap<String, serde_json::Value>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct GetDailyNotesRangeResult {
+    pub notes: Vec<DailyNoteEntry>,
+    #[schemars(description = "Requested dates within the range that had no daily note")]
+    pub missing_dates: Vec<String>,
+    #[schemars(description = "Present only when rollup was requested: each found day's body concatenated under a date header")]
+    pub rollup: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct SearchVaultRequest {
-    #[schemars(description = "Search query - literal text (case-sensitive substring match)")]
+    #[schemars(description = "Search query - literal text (case-sensitive substring match for 'filename'/'tags' scope, BM25-ranked token match for 'content' scope)")]
     pub query: String,
     #[schemars(description = "Search scope: array of 'content' (note body), 'filename' (file paths), 'tags' (frontmatter tags). Can specify multiple. Default: ['content', 'filename']")]
     pub scope: Option<Vec<String>>,
     #[schemars(description = "Limit search to specific subdirectory relative to vault root")]
     pub path_filter: Option<String>,
+    #[schemars(description = "If true, content-scope query terms also match index terms within a small edit distance (typo tolerance). Default: false")]
+    pub fuzzy: Option<bool>,
+    #[schemars(description = "Maximum number of results to return (default: 50)")]
+    pub limit: Option<u32>,
+    #[schemars(description = "Pagination offset into the ranked results (default: 0)")]
+    pub offset: Option<u32>,
+    #[schemars(description = "Patterns a path must match at least one of to be searched (e.g. \"path:Projects/\", \"rootfilesin:Daily Notes\", \"**/archive/**\"). Default: the whole vault")]
+    pub include: Option<Vec<String>>,
+    #[schemars(description = "Patterns a path must not match to be searched, applied on top of the configured default_exclude_patterns (e.g. .obsidian/, .trash/)")]
+    pub exclude: Option<Vec<String>>,
+    #[schemars(description = "Predicates over frontmatter properties, e.g. \"status = \\\"done\\\"\", \"tags contains work\", \"created >= 2024-01-01\", \"priority > 2\", \"archived exists\". Combined per filter_mode; a note must also satisfy these to be returned")]
+    pub filters: Option<Vec<String>>,
+    #[schemars(description = "How to combine multiple `filters`: \"and\" (default) or \"or\"")]
+    pub filter_mode: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct SearchResult {
     pub path: String,
     pub match_preview: Option<String>,
+    #[schemars(description = "BM25 relevance score for content-scope matches; absent for filename/tag matches")]
+    pub score: Option<f64>,
+    #[schemars(description = "For fuzzy content matches where the matched index term differs from what was typed, \"query_term -> matched_term\" pairs, one per substitution")]
+    pub fuzzy_matches: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct SearchRankedRequest {
+    #[schemars(description = "Search query: tokenized and matched against the persisted BM25 index over note body, title, and frontmatter tags (title/tag matches are boosted over body matches)")]
+    pub query: String,
+    #[schemars(description = "Limit search to specific subdirectory relative to vault root")]
+    pub path_filter: Option<String>,
+    #[schemars(description = "If true, query terms also match index terms within a small edit distance (typo tolerance). Default: false")]
+    pub fuzzy: Option<bool>,
+    #[schemars(description = "Maximum number of results to return (default: 50)")]
+    pub limit: Option<u32>,
+    #[schemars(description = "Pagination offset into the ranked results (default: 0)")]
+    pub offset: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct QueryNotesRequest {
+    #[schemars(description = "JSONPath expression evaluated against each note's frontmatter as the JSON root, e.g. \"$[?(@.status=='active')]\". A note matches if the expression selects anything")]
+    pub query: String,
+    #[schemars(description = "Limit the query to a specific subdirectory relative to vault root")]
+    pub path_filter: Option<String>,
+    #[schemars(description = "Frontmatter keys to project into each result's values map. Defaults to the full frontmatter")]
+    pub select: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct QueryNotesMatch {
+    pub path: String,
+    pub values: JsonMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct FacetDistributionRequest {
+    #[schemars(description = "Frontmatter property to tabulate, e.g. \"status\" or \"tags\". Array-valued properties count each element separately")]
+    pub property: String,
+    #[schemars(description = "Limit to a specific subdirectory relative to vault root")]
+    pub path_filter: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct FacetValue {
+    pub value: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct FacetDistributionResult {
+    pub property: String,
+    pub values: Vec<FacetValue>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ReindexVaultRequest {
+    #[schemars(description = "If true, discard the existing on-disk index and rescan every note. If false (default), only notes whose mtime changed since the last index are re-parsed")]
+    pub full: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ReindexResult {
+    pub documents_indexed: usize,
+    pub terms: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct PushVaultRequest {
+    #[schemars(description = "Commit message. If omitted, an auto-generated timestamped message is used")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct PullVaultRequest {}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct DumpVaultRequest {
+    #[schemars(description = "Path to write the gzipped tar archive to, relative to the current directory. Defaults to 'vault-dump-<timestamp>.tar.gz'")]
+    pub output_path: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct LoadVaultRequest {
+    #[schemars(description = "Path to a gzipped tar archive previously produced by dump_vault")]
+    pub input_path: String,
+    #[schemars(description = "If true, existing files are overwritten by the archive's contents. Default: false (existing files are left untouched)")]
+    pub overwrite: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ExportNoteRequest {
+    #[schemars(description = "Path to the note file relative to vault root. Can include or omit .md extension (auto-added)")]
+    pub path: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ExportVaultRequest {
+    #[schemars(description = "Directory to write the exported vault's standard markdown into, preserving the vault's relative directory structure")]
+    pub destination: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
@@ -102,6 +270,16 @@ pub struct FindRelatedNotesRequest {
     pub path: String,
     #[schemars(description = "Relationship criteria: array of 'tags' and/or 'links'. Extracts tags from frontmatter and wikilinks [[...]] from content, then finds notes with matching tags or filenames. Default: ['tags', 'links']")]
     pub on: Option<Vec<String>>,
+    #[schemars(description = "Patterns a candidate path must match at least one of (e.g. \"path:Projects/\", \"rootfilesin:Daily Notes\", \"**/archive/**\"). Default: the whole vault")]
+    pub include: Option<Vec<String>>,
+    #[schemars(description = "Patterns a candidate path must not match, applied on top of the configured default_exclude_patterns")]
+    pub exclude: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct GetBacklinksRequest {
+    #[schemars(description = "Path to the note relative to vault root whose backlinks to list. Can include or omit .md extension (auto-added)")]
+    pub path: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
@@ -136,14 +314,139 @@ pub struct UpdateNotePropertiesRequest {
     pub remove: Option<Vec<String>>,
 }
 
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct GetNoteFrontmatterRequest {
+    #[schemars(description = "Path to the note file relative to vault root. Can include or omit .md extension (auto-added)")]
+    pub path: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct NoteFrontmatterResult {
+    pub path: String,
+    #[schemars(description = "Parsed YAML frontmatter as JSON, or null if the note has no leading --- block")]
+    pub frontmatter: Option<JsonMap<String, serde_json::Value>>,
+    #[schemars(description = "Set if the note could not be read, or its frontmatter block could not be parsed as YAML")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct UpdateNoteFrontmatterRequest {
+    #[schemars(description = "Path to the note file relative to vault root. Can include or omit .md extension (auto-added)")]
+    pub path: String,
+    #[schemars(description = "JSON object merged into the existing frontmatter. New keys are added, existing keys are overwritten. The body is left untouched")]
+    pub frontmatter: JsonMap<String, serde_json::Value>,
+}
+
+/// Controls how a rendered template's own frontmatter block and the
+/// caller-supplied `variables` are combined in `create_note_from_template`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FrontmatterStrategy {
+    /// Merge `variables` into the rendered frontmatter only if the template
+    /// already has a leading `---` block.
+    Auto,
+    /// Always merge `variables` into the frontmatter, creating one if absent.
+    Always,
+    /// Never touch frontmatter; the rendered template is written as-is.
+    Never,
+}
+
+impl FrontmatterStrategy {
+    /// Parses "auto"/"always"/"never" case-insensitively, defaulting to Auto.
+    fn parse(raw: Option<&str>) -> FrontmatterStrategy {
+        match raw.map(|s| s.to_ascii_lowercase()).as_deref() {
+            Some("always") => FrontmatterStrategy::Always,
+            Some("never") => FrontmatterStrategy::Never,
+            _ => FrontmatterStrategy::Auto,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct CreateNoteFromTemplateRequest {
     #[schemars(description = "Destination path for the new note relative to vault root. SHOULD include .md extension")]
     pub path: String,
     #[schemars(description = "Template path: if starts with '/' or contains ':', treated as absolute path relative to vault root; otherwise relative to templates directory (paths from list_notes_templates can be used directly)")]
     pub template_path: String,
-    #[schemars(description = "Key-value pairs for template substitution. Replaces {{variable}} placeholders in template")]
-    pub variables: Option<HashMap<String, String>>,
+    #[schemars(description = "Structured variables for template rendering (a JSON object), supporting nested paths (e.g. {\"author\": {\"name\": \"...\"}}) and arrays for {{#each}}. 'date', 'time', 'title', and 'uuid' are filled in automatically when not supplied (and can be overridden here). Rendering is strict: a variable referenced in the template but missing here is reported as an error")]
+    pub variables: Option<serde_json::Value>,
+    #[schemars(description = "strftime format for the built-in 'date' variable. Defaults to ISO-8601 (%Y-%m-%d)")]
+    pub date_format: Option<String>,
+    #[schemars(description = "strftime format for the built-in 'time' variable. Defaults to ISO-8601 (%H:%M:%S)")]
+    pub time_format: Option<String>,
+    #[schemars(description = "How 'variables' should be merged into the rendered template's own frontmatter block: 'auto' (default, merge only if the template already has a --- block), 'always' (merge, creating a frontmatter block if absent), or 'never' (leave the rendered template untouched)")]
+    pub frontmatter_strategy: Option<String>,
+}
+
+/// A single variable a template expects, discovered either from a
+/// `{{placeholder}}` in the template body or a declaration in the
+/// template's own `variables:` frontmatter list.
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct TemplateVariable {
+    pub name: String,
+    #[schemars(description = "Taken from the 'required' key of the template's own frontmatter declaration when set; otherwise false only if that declaration gives a default for this variable")]
+    pub required: bool,
+    pub description: Option<String>,
+    pub default: Option<serde_json::Value>,
+}
+
+/// A template's path/name/size plus its declared or discovered variables,
+/// so a caller can know exactly what to pass to create_note_from_template
+/// instead of guessing and getting literal {{...}} left in the output.
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct TemplateManifest {
+    pub path: String,
+    pub name: String,
+    pub size: Option<u64>,
+    pub variables: Vec<TemplateVariable>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct DescribeNoteTemplateRequest {
+    #[schemars(description = "Template path: if starts with '/' or contains ':', treated as absolute path relative to vault root; otherwise relative to templates directory (paths from list_notes_templates can be used directly)")]
+    pub template_path: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct DescribeNoteTemplateResult {
+    pub manifest: Option<TemplateManifest>,
+    #[schemars(description = "Set if the template file could not be found or read")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct MoveNoteRequest {
+    #[schemars(description = "Current path of the note relative to vault root. Can include or omit .md extension (auto-added)")]
+    pub from: String,
+    #[schemars(description = "Destination path relative to vault root. Can include or omit .md extension (auto-added)")]
+    pub to: String,
+    #[schemars(description = "If true (default), rewrite [[wikilinks]] across the vault that point at the old path to point at the new one. [[target#heading]] anchors and [[target|alias]] aliases are preserved")]
+    pub update_links: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct MoveNoteResult {
+    pub success: bool,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub error: Option<String>,
+    #[schemars(description = "Number of wikilinks rewritten across the vault")]
+    pub links_updated: usize,
+    #[schemars(description = "Paths of notes whose wikilinks were rewritten")]
+    pub updated_files: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ListNoteHistoryRequest {
+    #[schemars(description = "Path to the note relative to vault root. Can include or omit .md extension (auto-added)")]
+    pub path: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct RestoreNoteVersionRequest {
+    #[schemars(description = "Path to the note relative to vault root. Can include or omit .md extension (auto-added)")]
+    pub path: String,
+    #[schemars(description = "Timestamp of the version to restore, as returned by list_note_history")]
+    pub timestamp: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
@@ -166,6 +469,10 @@ impl ObsidianService {
         weekly_notes_path: Option<&str>,
         monthly_notes_path: Option<&str>,
         templates_path: Option<&str>,
+        git_remote: Option<&str>,
+        git_branch: Option<&str>,
+        default_exclude_patterns: Vec<String>,
+        default_frontmatter: JsonMap<String, serde_json::Value>,
     ) -> Result<Self> {
         let vault_path = PathBuf::from(vault_root);
         if !vault_path.exists() {
@@ -183,6 +490,14 @@ impl ObsidianService {
             weekly_notes_path: weekly,
             monthly_notes_path: monthly,
             templates_path: templates,
+            git_remote: git_remote.map(String::from),
+            git_branch: git_branch.map(String::from),
+            default_exclude_patterns,
+            postprocessors: vec![
+                Box::new(postprocessor::NormalizeLineEndings),
+                Box::new(postprocessor::EnsureTrailingNewline),
+                Box::new(postprocessor::MergeFrontmatter::new(default_frontmatter)),
+            ],
             tool_router: Self::tool_router(),
         })
     }
@@ -290,6 +605,25 @@ impl ObsidianService {
         }
     }
 
+    // Helper: Parse frontmatter from content, surfacing a malformed YAML
+    // block as an error instead of silently falling back to "no frontmatter"
+    // (unlike parse_frontmatter, used where the caller can report a real error).
+    fn try_parse_frontmatter(content: &str) -> Result<(Option<JsonMap<String, serde_json::Value>>, String), String> {
+        if !content.starts_with("---\n") {
+            return Ok((None, content.to_string()));
+        }
+
+        let Some(end_pos) = content[4..].find("\n---\n") else {
+            return Ok((None, content.to_string()));
+        };
+        let yaml_str = &content[4..end_pos + 4];
+        let body = &content[end_pos + 9..];
+
+        serde_yaml::from_str::<JsonMap<String, serde_json::Value>>(yaml_str)
+            .map(|fm| (Some(fm), body.to_string()))
+            .map_err(|e| format!("Invalid frontmatter YAML: {}", e))
+    }
+
     // Helper: Format content with frontmatter
     fn format_with_frontmatter(content: &str, frontmatter: Option<&JsonMap<String, serde_json::Value>>) -> String {
         if let Some(fm) = frontmatter {
@@ -343,8 +677,15 @@ impl ObsidianService {
     #[tool(description = "List files and directories in a vault directory. Returns both files and directories by default. When recursive=true, only returns .md files from subdirectories. Path is relative to vault root (use '.' for root). Returns empty array if path doesn't exist.")]
     pub fn list_notes_directory(
         &self,
-        Parameters(ListNotesDirectoryRequest { path, limit, offset, recursive }): Parameters<ListNotesDirectoryRequest>,
+        params: Parameters<ListNotesDirectoryRequest>,
     ) -> Json<Vec<DirectoryItem>> {
+        Json(self.list_notes_directory_impl(params.0))
+    }
+
+    pub fn list_notes_directory_impl(
+        &self,
+        ListNotesDirectoryRequest { path, limit, offset, recursive }: ListNotesDirectoryRequest,
+    ) -> Vec<DirectoryItem> {
         let path = path.unwrap_or_else(|| ".".to_string());
         let limit = limit.unwrap_or(50) as usize;
         let offset = offset.unwrap_or(0) as usize;
@@ -354,7 +695,7 @@ impl ObsidianService {
             Ok(p) => p,
             Err(e) => {
                 eprintln!("Invalid path: {}", e);
-                return Json(Vec::new());
+                return Vec::new();
             }
         };
 
@@ -404,40 +745,47 @@ impl ObsidianService {
             }
         }
 
-        Json(items)
+        items
     }
 
     #[tool(description = "Read a markdown note file from the vault. Path can include or omit .md extension (auto-added if missing). Path is relative to vault root. Returns content body (without frontmatter) and frontmatter separately as YAML metadata.")]
     pub fn read_notes_file(
         &self,
-        Parameters(ReadNotesFileRequest { path }): Parameters<ReadNotesFileRequest>,
+        params: Parameters<ReadNotesFileRequest>,
     ) -> Json<FileContent> {
+        Json(self.read_notes_file_impl(params.0))
+    }
+
+    pub fn read_notes_file_impl(
+        &self,
+        ReadNotesFileRequest { path }: ReadNotesFileRequest,
+    ) -> FileContent {
         let path_with_ext = self.ensure_md_extension(&path);
         match self.validate_path(&path_with_ext) {
             Ok(full_path) => {
                 match fs::read_to_string(&full_path) {
                     Ok(content) => {
                         let (frontmatter, body) = Self::parse_frontmatter(&content);
-                        Json(FileContent {
+                        FileContent {
                             content: body,
                             frontmatter,
-                        })
+                        }
                     }
                     Err(e) => {
                         eprintln!("Failed to read file {}: {}", path_with_ext, e);
-                        Json(FileContent {
+                        FileContent {
                             content: format!("Error reading file: {}", e),
                             frontmatter: None,
-                        })
+                        }
                     }
                 }
             }
             Err(e) => {
                 eprintln!("Invalid path {}: {}", path_with_ext, e);
-                Json(FileContent {
+                FileContent {
                     content: format!("Error: {}", e),
                     frontmatter: None,
-                })
+                }
             }
         }
     }
@@ -445,8 +793,15 @@ impl ObsidianService {
     #[tool(description = "Delete a file or directory from the vault. For files, path can include or omit .md extension. For directories, path must not include .md extension. Deletes directories recursively. Path is relative to vault root. Returns error if path doesn't exist.")]
     pub fn delete_notes_item(
         &self,
-        Parameters(DeleteNotesItemRequest { path }): Parameters<DeleteNotesItemRequest>,
+        params: Parameters<DeleteNotesItemRequest>,
     ) -> Json<OperationResult> {
+        Json(self.delete_notes_item_impl(params.0))
+    }
+
+    pub fn delete_notes_item_impl(
+        &self,
+        DeleteNotesItemRequest { path }: DeleteNotesItemRequest,
+    ) -> OperationResult {
         // Try with .md extension first (for files), then without (for directories)
         let path_with_ext = self.ensure_md_extension(&path);
         let result = match self.validate_path(&path_with_ext) {
@@ -454,6 +809,7 @@ impl ObsidianService {
                 if full_path.is_dir() {
                     fs::remove_dir_all(&full_path)
                 } else {
+                    note_history::snapshot(&self.vault_root, &path_with_ext, &full_path);
                     fs::remove_file(&full_path)
                 }
             }
@@ -464,17 +820,18 @@ impl ObsidianService {
                         if full_path.is_dir() {
                             fs::remove_dir_all(&full_path)
                         } else {
+                            note_history::snapshot(&self.vault_root, &path, &full_path);
                             fs::remove_file(&full_path)
                         }
                     }
                     Err(e) => {
                         eprintln!("Invalid path {}: {}", path, e);
-                        return Json(OperationResult {
+                        return OperationResult {
                             success: false,
                             path: None,
                             error: Some(format!("{}", e)),
                             deleted_path: None,
-                        });
+                        };
                     }
                 }
             }
@@ -482,20 +839,212 @@ impl ObsidianService {
 
         match result {
                 
-            Ok(_) => Json(OperationResult {
+            Ok(_) => OperationResult {
                 success: true,
                 path: None,
                 error: None,
                 deleted_path: Some(path),
-            }),
+            },
             Err(e) => {
                 eprintln!("Failed to delete {}: {}", path, e);
-                Json(OperationResult {
+                OperationResult {
                     success: false,
                     path: None,
                     error: Some(format!("{}", e)),
                     deleted_path: None,
-                })
+                }
+            }
+        }
+    }
+
+    #[tool(description = "Move or rename a note. from/to are relative to vault root (.md extension auto-added if omitted). Creates parent directories for the destination as needed; falls back to copy+delete if the move crosses filesystem boundaries. When update_links is true (default), every [[wikilink]] across the vault pointing at the old note is rewritten to the new path/basename, preserving [[target#heading]] anchors and [[target|alias]] aliases.")]
+    pub fn move_note(
+        &self,
+        params: Parameters<MoveNoteRequest>,
+    ) -> Json<MoveNoteResult> {
+        Json(self.move_note_impl(params.0))
+    }
+
+    pub fn move_note_impl(
+        &self,
+        MoveNoteRequest { from, to, update_links }: MoveNoteRequest,
+    ) -> MoveNoteResult {
+        let from_path = self.ensure_md_extension(&from);
+        let to_path = self.ensure_md_extension(&to);
+
+        let full_from = match self.validate_path(&from_path) {
+            Ok(p) => p,
+            Err(e) => {
+                eprintln!("Invalid path {}: {}", from_path, e);
+                return MoveNoteResult {
+                    success: false,
+                    from: None,
+                    to: None,
+                    error: Some(format!("{}", e)),
+                    links_updated: 0,
+                    updated_files: Vec::new(),
+                };
+            }
+        };
+
+        let full_to = self.vault_root.join(&to_path);
+        if let Some(parent) = full_to.parent() {
+            if let Err(e) = fs::create_dir_all(parent) {
+                eprintln!("Failed to create directory: {}", e);
+                return MoveNoteResult {
+                    success: false,
+                    from: None,
+                    to: None,
+                    error: Some(format!("{}", e)),
+                    links_updated: 0,
+                    updated_files: Vec::new(),
+                };
+            }
+        }
+
+        // If `to` already exists, snapshot it before it's clobbered by the
+        // move.
+        note_history::snapshot(&self.vault_root, &to_path, &full_to);
+
+        let move_result = fs::rename(&full_from, &full_to).or_else(|_| {
+            fs::copy(&full_from, &full_to)?;
+            fs::remove_file(&full_from)
+        });
+        if let Err(e) = move_result {
+            eprintln!("Failed to move {}: {}", from_path, e);
+            return MoveNoteResult {
+                success: false,
+                from: None,
+                to: None,
+                error: Some(format!("{}", e)),
+                links_updated: 0,
+                updated_files: Vec::new(),
+            };
+        }
+
+        let (links_updated, updated_files) = if update_links.unwrap_or(true) {
+            self.rewrite_wikilinks(&from_path, &to_path)
+        } else {
+            (0, Vec::new())
+        };
+
+        MoveNoteResult {
+            success: true,
+            from: Some(from_path),
+            to: Some(to_path),
+            error: None,
+            links_updated,
+            updated_files,
+        }
+    }
+
+    // Helper: rewrite [[target]], [[target#heading]] and [[target|alias]] wikilinks
+    // across the vault that resolve to `old_rel_path` so they point at `new_rel_path`.
+    // Returns the number of links rewritten and the paths of affected notes.
+    fn rewrite_wikilinks(&self, old_rel_path: &str, new_rel_path: &str) -> (usize, Vec<String>) {
+        let old_stem = Path::new(old_rel_path)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or(old_rel_path);
+        let old_no_ext = old_rel_path.strip_suffix(".md").unwrap_or(old_rel_path);
+        let new_stem = Path::new(new_rel_path)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or(new_rel_path);
+        let new_no_ext = new_rel_path.strip_suffix(".md").unwrap_or(new_rel_path);
+
+        let link_regex = Regex::new(r"\[\[([^\]|#]+)(#[^\]|]*)?(\|[^\]]*)?\]\]").unwrap();
+
+        let mut links_updated = 0;
+        let mut updated_files = Vec::new();
+
+        for entry in WalkDir::new(&self.vault_root)
+            .into_iter()
+            .filter_map(|e| e.ok())
+        {
+            let entry_path = entry.path();
+            if !entry_path.is_file() || entry_path.extension().and_then(|s| s.to_str()) != Some("md") {
+                continue;
+            }
+
+            let Ok(content) = fs::read_to_string(entry_path) else {
+                continue;
+            };
+
+            let mut file_updated = false;
+            let new_content = link_regex.replace_all(&content, |caps: &regex::Captures| {
+                let target = caps.get(1).map(|m| m.as_str().trim()).unwrap_or("");
+                let heading = caps.get(2).map(|m| m.as_str()).unwrap_or("");
+                let alias = caps.get(3).map(|m| m.as_str()).unwrap_or("");
+
+                if target == old_stem || target == old_no_ext {
+                    file_updated = true;
+                    links_updated += 1;
+                    let replacement_target = if target == old_no_ext { new_no_ext } else { new_stem };
+                    format!("[[{}{}{}]]", replacement_target, heading, alias)
+                } else {
+                    caps.get(0).unwrap().as_str().to_string()
+                }
+            });
+
+            if file_updated {
+                if let Ok(rel_path) = entry_path.strip_prefix(&self.vault_root) {
+                    updated_files.push(rel_path.to_string_lossy().to_string());
+                }
+                let _ = fs::write(entry_path, new_content.to_string());
+            }
+        }
+
+        (links_updated, updated_files)
+    }
+
+    #[tool(description = "List saved history versions of a note, oldest first. Versions are snapshotted automatically before a single note is overwritten or deleted by the note-editing tools (see skip_history on create_or_update_note); deleting a whole directory, the wikilink rewrites done by move_note, and load_vault with overwrite=true do not snapshot. Returns each version's timestamp and size; pass a timestamp to restore_note_version to roll back.")]
+    pub fn list_note_history(
+        &self,
+        params: Parameters<ListNoteHistoryRequest>,
+    ) -> Json<Vec<HistoryVersion>> {
+        Json(self.list_note_history_impl(params.0))
+    }
+
+    pub fn list_note_history_impl(
+        &self,
+        ListNoteHistoryRequest { path }: ListNoteHistoryRequest,
+    ) -> Vec<HistoryVersion> {
+        let path_with_ext = self.ensure_md_extension(&path);
+        note_history::list_history(&self.vault_root, &path_with_ext)
+    }
+
+    #[tool(description = "Atomically restore a note to a previously saved version. timestamp must match one returned by list_note_history. The current content is itself snapshotted to history before being overwritten.")]
+    pub fn restore_note_version(
+        &self,
+        params: Parameters<RestoreNoteVersionRequest>,
+    ) -> Json<OperationResult> {
+        Json(self.restore_note_version_impl(params.0))
+    }
+
+    pub fn restore_note_version_impl(
+        &self,
+        RestoreNoteVersionRequest { path, timestamp }: RestoreNoteVersionRequest,
+    ) -> OperationResult {
+        let path_with_ext = self.ensure_md_extension(&path);
+        let full_path = self.vault_root.join(&path_with_ext);
+
+        note_history::snapshot(&self.vault_root, &path_with_ext, &full_path);
+        match note_history::restore_version(&self.vault_root, &path_with_ext, &full_path, &timestamp) {
+            Ok(()) => OperationResult {
+                success: true,
+                path: Some(path_with_ext),
+                error: None,
+                deleted_path: None,
+            },
+            Err(e) => {
+                eprintln!("Failed to restore version: {}", e);
+                OperationResult {
+                    success: false,
+                    path: None,
+                    error: Some(e),
+                    deleted_path: None,
+                }
             }
         }
     }
@@ -503,30 +1052,41 @@ impl ObsidianService {
     #[tool(description = "Create a new note or update existing one. Path should NOT include .md extension (auto-added). Mode options: 'overwrite' (default) - replaces entire file, 'append' - adds content after existing body, 'prepend' - adds content before existing body. Frontmatter is merged (new keys added, existing keys updated). Creates parent directories if needed. Path is relative to vault root.")]
     pub fn create_or_update_note(
         &self,
-        Parameters(CreateOrUpdateNoteRequest { path, content, frontmatter, mode }): Parameters<CreateOrUpdateNoteRequest>,
+        params: Parameters<CreateOrUpdateNoteRequest>,
     ) -> Json<OperationResult> {
+        Json(self.create_or_update_note_impl(params.0))
+    }
+
+    pub fn create_or_update_note_impl(
+        &self,
+        CreateOrUpdateNoteRequest { path, content, frontmatter, mode, skip_history }: CreateOrUpdateNoteRequest,
+    ) -> OperationResult {
         let md_path = self.ensure_md_extension(&path);
         let full_path = self.vault_root.join(&md_path);
 
+        if !skip_history.unwrap_or(false) {
+            note_history::snapshot(&self.vault_root, &md_path, &full_path);
+        }
+
         // Create parent directory if needed
         if let Some(parent) = full_path.parent() {
             if let Err(e) = fs::create_dir_all(parent) {
                 eprintln!("Failed to create directory: {}", e);
-                return Json(OperationResult {
+                return OperationResult {
                     success: false,
                     path: None,
                     error: Some(format!("{}", e)),
                     deleted_path: None,
-                });
+                };
             }
         }
 
         let mode = mode.as_deref().unwrap_or("overwrite");
-        let final_content = if full_path.exists() && mode != "overwrite" {
+        let (mut body, merged_fm) = if full_path.exists() && mode != "overwrite" {
             match fs::read_to_string(&full_path) {
                 Ok(existing) => {
                     let (existing_fm, existing_body) = Self::parse_frontmatter(&existing);
-                    
+
                     let merged_fm = match (existing_fm, &frontmatter) {
                         (Some(mut fm), Some(new_fm)) => {
                             fm.extend(new_fm.clone());
@@ -543,37 +1103,48 @@ impl ObsidianService {
                         _ => existing_body,
                     };
 
-                    Self::format_with_frontmatter(&body, merged_fm.as_ref())
+                    (body, merged_fm)
                 }
                 Err(e) => {
                     eprintln!("Failed to read existing file: {}", e);
-                    return Json(OperationResult {
+                    return OperationResult {
                         success: false,
                         path: None,
                         error: Some(format!("{}", e)),
                         deleted_path: None,
-                    });
+                    };
                 }
             }
         } else {
-            Self::format_with_frontmatter(&content, frontmatter.as_ref())
+            (content, frontmatter)
         };
 
-        match fs::write(&full_path, final_content) {
-            Ok(_) => Json(OperationResult {
+        let mut ctx = postprocessor::NoteContext { path: md_path.clone(), frontmatter: merged_fm };
+        if !postprocessor::run_pipeline(&self.postprocessors, &mut ctx, &mut body) {
+            return OperationResult {
+                success: false,
+                path: None,
+                error: Some("Note write skipped by postprocessor pipeline".to_string()),
+                deleted_path: None,
+            };
+        }
+        let final_content = Self::format_with_frontmatter(&body, ctx.frontmatter.as_ref());
+
+        match note_history::atomic_write(&full_path, &final_content) {
+            Ok(_) => OperationResult {
                 success: true,
                 path: Some(md_path),
                 error: None,
                 deleted_path: None,
-            }),
+            },
             Err(e) => {
                 eprintln!("Failed to write file: {}", e);
-                Json(OperationResult {
+                OperationResult {
                     success: false,
                     path: None,
                     error: Some(format!("{}", e)),
                     deleted_path: None,
-                })
+                }
             }
         }
     }
@@ -581,8 +1152,15 @@ impl ObsidianService {
     #[tool(description = "Get daily note for a date. Date can be 'today' (default), 'yesterday', 'tomorrow', or 'YYYY-MM-DD' format. Searches common locations: configured daily_notes_path, root, 'daily/', 'Daily Notes/'. Returns error message in content field if note not found.")]
     pub fn get_daily_note(
         &self,
-        Parameters(GetDailyNoteRequest { date }): Parameters<GetDailyNoteRequest>,
+        params: Parameters<GetDailyNoteRequest>,
     ) -> Json<FileContent> {
+        Json(self.get_daily_note_impl(params.0))
+    }
+
+    pub fn get_daily_note_impl(
+        &self,
+        GetDailyNoteRequest { date }: GetDailyNoteRequest,
+    ) -> FileContent {
         match Self::parse_date(date.as_ref()) {
             Ok(target_date) => {
                 match self.find_daily_note(target_date) {
@@ -590,71 +1168,173 @@ impl ObsidianService {
                         match fs::read_to_string(&note_path) {
                             Ok(content) => {
                                 let (frontmatter, body) = Self::parse_frontmatter(&content);
-                                Json(FileContent {
+                                FileContent {
                                     content: body,
                                     frontmatter,
-                                })
+                                }
                             }
                             Err(e) => {
                                 eprintln!("Failed to read daily note: {}", e);
-                                Json(FileContent {
+                                FileContent {
                                     content: format!("Error reading file: {}", e),
                                     frontmatter: None,
-                                })
+                                }
                             }
                         }
                     }
                     Err(e) => {
                         eprintln!("Daily note not found: {}", e);
-                        Json(FileContent {
+                        FileContent {
                             content: format!("Error: {}", e),
                             frontmatter: None,
-                        })
+                        }
                     }
                 }
             }
             Err(e) => {
                 eprintln!("Invalid date: {}", e);
-                Json(FileContent {
+                FileContent {
                     content: format!("Error: {}", e),
                     frontmatter: None,
-                })
+                }
             }
         }
     }
 
-    #[tool(description = "Search for text in vault notes. Query is literal text (case-sensitive substring match). Scope options: 'content' (note body), 'filename' (file paths), 'tags' (frontmatter tags). Can specify multiple scopes. path_filter limits search to specific subdirectory (relative to vault root). Returns file paths and match previews.")]
-    pub fn search_vault(
+    #[tool(description = "Get daily notes across an inclusive date range in one call, so a client doesn't have to call get_daily_note once per day. 'from'/'to' accept 'today', 'yesterday', 'tomorrow', or 'YYYY-MM-DD'. Days with no daily note are silently skipped and listed in missing_dates. Set rollup=true to also get the found bodies concatenated under '## YYYY-MM-DD' headers. Ranges longer than 366 days are rejected.")]
+    pub fn get_daily_notes_range(
         &self,
-        Parameters(SearchVaultRequest { query, scope, path_filter }): Parameters<SearchVaultRequest>,
-    ) -> Json<Vec<SearchResult>> {
-        let scope = scope.unwrap_or_else(|| vec!["content".to_string(), "filename".to_string()]);
-        let query_regex = Regex::new(&regex::escape(&query)).ok();
-        let mut results = Vec::new();
+        params: Parameters<GetDailyNotesRangeRequest>,
+    ) -> Json<GetDailyNotesRangeResult> {
+        Json(self.get_daily_notes_range_impl(params.0))
+    }
 
-        let search_root = if let Some(filter) = path_filter {
-            match self.validate_path(&filter) {
+    pub fn get_daily_notes_range_impl(
+        &self,
+        GetDailyNotesRangeRequest { from, to, rollup }: GetDailyNotesRangeRequest,
+    ) -> GetDailyNotesRangeResult {
+        let empty = || GetDailyNotesRangeResult {
+            notes: Vec::new(),
+            missing_dates: Vec::new(),
+            rollup: None,
+        };
+
+        let from_date = match Self::parse_date(Some(&from)) {
+            Ok(d) => d,
+            Err(e) => {
+                eprintln!("Invalid 'from' date: {}", e);
+                return empty();
+            }
+        };
+        let to_date = match Self::parse_date(Some(&to)) {
+            Ok(d) => d,
+            Err(e) => {
+                eprintln!("Invalid 'to' date: {}", e);
+                return empty();
+            }
+        };
+
+        if to_date < from_date {
+            eprintln!("'to' date {} is before 'from' date {}", to_date, from_date);
+            return empty();
+        }
+        if (to_date - from_date).num_days() >= MAX_DAILY_NOTE_RANGE_DAYS {
+            eprintln!(
+                "Date range from {} to {} exceeds the {}-day limit",
+                from_date, to_date, MAX_DAILY_NOTE_RANGE_DAYS
+            );
+            return empty();
+        }
+
+        let mut notes = Vec::new();
+        let mut missing_dates = Vec::new();
+        let mut date = from_date;
+
+        while date <= to_date {
+            let date_str = date.format("%Y-%m-%d").to_string();
+            match self.find_daily_note(date).and_then(|p| Ok((fs::read_to_string(&p)?, p))) {
+                Ok((content, note_path)) => {
+                    let (frontmatter, body) = Self::parse_frontmatter(&content);
+                    let rel_path = note_path
+                        .strip_prefix(&self.vault_root)
+                        .map(|p| p.to_string_lossy().to_string())
+                        .unwrap_or_else(|_| note_path.to_string_lossy().to_string());
+                    notes.push(DailyNoteEntry {
+                        date: date_str,
+                        path: rel_path,
+                        content: body,
+                        frontmatter,
+                    });
+                }
+                Err(_) => missing_dates.push(date_str),
+            }
+            date += chrono::Duration::days(1);
+        }
+
+        let rollup = if rollup.unwrap_or(false) {
+            Some(
+                notes
+                    .iter()
+                    .map(|n| format!("## {}\n\n{}", n.date, n.content))
+                    .collect::<Vec<_>>()
+                    .join("\n\n"),
+            )
+        } else {
+            None
+        };
+
+        GetDailyNotesRangeResult { notes, missing_dates, rollup }
+    }
+
+    #[tool(description = "Search for text in vault notes. 'filename'/'tags' scope use literal case-sensitive substring matching. 'content' scope tokenizes the query and ranks notes by BM25 relevance, optionally with typo-tolerant fuzzy matching (when enabled, the last query word also matches as a prefix). path_filter limits search to a specific subdirectory (relative to vault root); include/exclude take composable path: / rootfilesin: / glob patterns for finer-grained scoping on top of path_filter, and default_exclude_patterns from config is always subtracted. filters/filter_mode additionally require each result's frontmatter to satisfy a set of equality/membership/existence/range predicates (see get_facet_distribution to discover what values exist). limit/offset paginate the ranked results. Returns file paths, match previews built around the matched text, and (for content matches) a relevance score and any fuzzy_matches substitutions.")]
+    pub fn search_vault(
+        &self,
+        params: Parameters<SearchVaultRequest>,
+    ) -> Json<Vec<SearchResult>> {
+        Json(self.search_vault_impl(params.0))
+    }
+
+    pub fn search_vault_impl(
+        &self,
+        SearchVaultRequest { query, scope, path_filter, fuzzy, limit, offset, include, exclude, filters, filter_mode }: SearchVaultRequest,
+    ) -> Vec<SearchResult> {
+        let scope = scope.unwrap_or_else(|| vec!["content".to_string(), "filename".to_string()]);
+        let fuzzy = fuzzy.unwrap_or(false);
+        let limit = limit.unwrap_or(50) as usize;
+        let offset = offset.unwrap_or(0) as usize;
+        let query_regex = Regex::new(&regex::escape(&query)).ok();
+        let mut results = Vec::new();
+
+        let search_root = if let Some(filter) = &path_filter {
+            match self.validate_path(filter) {
                 Ok(p) => p,
                 Err(e) => {
                     eprintln!("Invalid path filter: {}", e);
-                    return Json(Vec::new());
+                    return Vec::new();
                 }
             }
         } else {
             self.vault_root.clone()
         };
+        let matcher = path_matcher::build_matcher(
+            &include.unwrap_or_default(),
+            &exclude.unwrap_or_default(),
+            &self.default_exclude_patterns,
+        );
+        let in_scope = |rel_path_str: &str| -> bool {
+            self.vault_root.join(rel_path_str).starts_with(&search_root) && matcher.is_match(rel_path_str)
+        };
 
-        for entry in WalkDir::new(&search_root)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            let entry_path = entry.path();
-            if !entry_path.is_file() || entry_path.extension().and_then(|s| s.to_str()) != Some("md") {
-                continue;
-            }
+        // The persisted index tracks every note's path, tags and mtime, so
+        // filename/tag lookups consult it instead of re-walking and
+        // re-reading the vault on every search.
+        let index = SearchIndex::load_and_refresh(&self.vault_root);
 
-            if let Ok(rel_path) = entry_path.strip_prefix(&self.vault_root) {
-                let rel_path_str = rel_path.to_string_lossy().to_string();
+        if scope.contains(&"filename".to_string()) || scope.contains(&"tags".to_string()) {
+            for rel_path_str in index.note_paths() {
+                if !in_scope(&rel_path_str) {
+                    continue;
+                }
 
                 // Search filename
                 if scope.contains(&"filename".to_string()) {
@@ -663,54 +1343,26 @@ impl ObsidianService {
                             results.push(SearchResult {
                                 path: rel_path_str.clone(),
                                 match_preview: Some(format!("Filename match: {}", rel_path_str)),
+                                score: None,
+                                fuzzy_matches: None,
                             });
                             continue;
                         }
                     }
                 }
 
-                // Search content and tags
-                if scope.contains(&"content".to_string()) || scope.contains(&"tags".to_string()) {
-                    if let Ok(content) = fs::read_to_string(entry_path) {
-                        // Search tags in frontmatter
-                        if scope.contains(&"tags".to_string()) {
-                            let (fm, _) = Self::parse_frontmatter(&content);
-                            if let Some(frontmatter) = fm {
-                                if let Some(tags) = frontmatter.get("tags") {
-                                    let tags_str = serde_json::to_string(tags).unwrap_or_default();
-                                    if let Some(re) = &query_regex {
-                                        if re.is_match(&tags_str) {
-                                            results.push(SearchResult {
-                                                path: rel_path_str.clone(),
-                                                match_preview: Some(format!("Tag match: {}", tags_str)),
-                                            });
-                                            continue;
-                                        }
-                                    }
-                                }
-                            }
-                        }
-
-                        // Search content
-                        if scope.contains(&"content".to_string()) {
-                            if let Some(re) = &query_regex {
-                                if let Some(mat) = re.find(&content) {
-                                    let start = mat.start().saturating_sub(50);
-                                    let end = (mat.end() + 50).min(content.len());
-                                    let preview = content[start..end].to_string();
-                                    results.push(SearchResult {
-                                        path: rel_path_str.clone(),
-                                        match_preview: Some(preview),
-                                    });
-                                }
-                            } else if content.contains(&query) {
-                                let idx = content.find(&query).unwrap_or(0);
-                                let start = idx.saturating_sub(50);
-                                let end = (idx + query.len() + 50).min(content.len());
-                                let preview = content[start..end].to_string();
+                // Search tags
+                if scope.contains(&"tags".to_string()) {
+                    let tags = index.tags_for(&rel_path_str);
+                    if !tags.is_empty() {
+                        let tags_str = serde_json::to_string(&tags).unwrap_or_default();
+                        if let Some(re) = &query_regex {
+                            if re.is_match(&tags_str) {
                                 results.push(SearchResult {
                                     path: rel_path_str.clone(),
-                                    match_preview: Some(preview),
+                                    match_preview: Some(format!("Tag match: {}", tags_str)),
+                                    score: None,
+                                    fuzzy_matches: None,
                                 });
                             }
                         }
@@ -719,122 +1371,412 @@ impl ObsidianService {
             }
         }
 
-        Json(results)
+        if scope.contains(&"content".to_string()) {
+            let terms = search_index::tokenize(&query);
+            if !terms.is_empty() {
+                for doc in index.search(&terms, fuzzy, |path| in_scope(path)) {
+                    let preview_terms = Self::preview_terms(&terms, &doc.matched_terms);
+                    let preview = fs::read_to_string(self.vault_root.join(&doc.path))
+                        .ok()
+                        .and_then(|content| Self::preview_around_terms(&content, &preview_terms));
+                    results.push(SearchResult {
+                        path: doc.path,
+                        match_preview: preview,
+                        score: Some(doc.score),
+                        fuzzy_matches: Self::format_fuzzy_matches(&doc.matched_terms),
+                    });
+                }
+            }
+        }
+
+        let filter_set = FilterSet::new(&filters.unwrap_or_default(), CombineMode::parse(filter_mode.as_deref()));
+        if !filter_set.is_empty() {
+            results.retain(|r| {
+                fs::read_to_string(self.vault_root.join(&r.path))
+                    .ok()
+                    .map(|content| Self::parse_frontmatter(&content).0)
+                    .map(|fm| filter_set.matches(&fm.unwrap_or_default()))
+                    .unwrap_or(false)
+            });
+        }
+
+        results.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        results.into_iter().skip(offset).take(limit).collect()
     }
 
-    #[tool(description = "Find notes related to a source note. Extracts tags from source note's frontmatter and wikilinks [[...]] from content. Finds other notes that: (1) have matching tags in frontmatter, or (2) have filenames matching extracted link names. 'on' parameter controls which relationships to use: 'tags' and/or 'links'. Path is relative to vault root. Returns empty array if source note not found.")]
-    pub fn find_related_notes(
+    #[tool(description = "Rank notes purely by BM25 relevance against the persisted search index, with title and tag matches boosted over body matches. Unlike search_vault's combined filename/tags/content scopes, this always scores the whole vault by content relevance. path_filter limits to a subdirectory; fuzzy enables typo-tolerant matching (with prefix matching on the last query word); limit/offset paginate the ranked results; fuzzy_matches on each result reports any query-term substitutions.")]
+    pub fn search_ranked(
         &self,
-        Parameters(FindRelatedNotesRequest { path, on }): Parameters<FindRelatedNotesRequest>,
+        params: Parameters<SearchRankedRequest>,
     ) -> Json<Vec<SearchResult>> {
-        let on = on.unwrap_or_else(|| vec!["tags".to_string(), "links".to_string()]);
-        
-        let path_with_ext = self.ensure_md_extension(&path);
-        let (frontmatter, body, full_path) = match self.validate_path(&path_with_ext) {
-            Ok(full_path) => {
-                match fs::read_to_string(&full_path) {
-                    Ok(content) => {
-                        let (fm, body) = Self::parse_frontmatter(&content);
-                        (fm, body, full_path)
-                    }
-                    Err(e) => {
-                        eprintln!("Failed to read file {}: {}", path_with_ext, e);
-                        return Json(Vec::new());
-                    }
+        Json(self.search_ranked_impl(params.0))
+    }
+
+    pub fn search_ranked_impl(
+        &self,
+        SearchRankedRequest { query, path_filter, fuzzy, limit, offset }: SearchRankedRequest,
+    ) -> Vec<SearchResult> {
+        let fuzzy = fuzzy.unwrap_or(false);
+        let limit = limit.unwrap_or(50) as usize;
+        let offset = offset.unwrap_or(0) as usize;
+
+        let search_root = if let Some(filter) = &path_filter {
+            match self.validate_path(filter) {
+                Ok(p) => p,
+                Err(e) => {
+                    eprintln!("Invalid path filter: {}", e);
+                    return Vec::new();
                 }
             }
-            Err(e) => {
-                eprintln!("Invalid path {}: {}", path_with_ext, e);
-                return Json(Vec::new());
+        } else {
+            self.vault_root.clone()
+        };
+        let in_scope = |rel_path_str: &str| -> bool {
+            self.vault_root.join(rel_path_str).starts_with(&search_root)
+        };
+
+        let terms = search_index::tokenize(&query);
+        if terms.is_empty() {
+            return Vec::new();
+        }
+
+        let index = SearchIndex::load_and_refresh(&self.vault_root);
+        index
+            .search(&terms, fuzzy, in_scope)
+            .into_iter()
+            .skip(offset)
+            .take(limit)
+            .map(|doc| {
+                let preview_terms = Self::preview_terms(&terms, &doc.matched_terms);
+                let preview = fs::read_to_string(self.vault_root.join(&doc.path))
+                    .ok()
+                    .and_then(|content| Self::preview_around_terms(&content, &preview_terms));
+                SearchResult {
+                    path: doc.path,
+                    match_preview: preview,
+                    score: Some(doc.score),
+                    fuzzy_matches: Self::format_fuzzy_matches(&doc.matched_terms),
+                }
+            })
+            .collect()
+    }
+
+    // Helper: build a preview window around the first occurrence of any query term
+    fn preview_around_terms(content: &str, terms: &[String]) -> Option<String> {
+        let lower = content.to_lowercase();
+        let idx = terms.iter().filter_map(|t| lower.find(t.as_str())).min()?;
+        let start = idx.saturating_sub(50);
+        let end = (idx + 50).min(content.len());
+        Some(content[start..end].to_string())
+    }
+
+    // Substitute any fuzzy-matched index term for its query term, so previews
+    // are built around the text actually in the note rather than a typo or
+    // partial word the user typed.
+    fn preview_terms(terms: &[String], matched_terms: &[(String, String)]) -> Vec<String> {
+        terms
+            .iter()
+            .map(|term| {
+                matched_terms
+                    .iter()
+                    .find(|(query_term, _)| query_term == term)
+                    .map(|(_, matched)| matched.clone())
+                    .unwrap_or_else(|| term.clone())
+            })
+            .collect()
+    }
+
+    // Render "query_term -> matched_term" pairs for fuzzy substitutions, if any.
+    fn format_fuzzy_matches(matched_terms: &[(String, String)]) -> Option<Vec<String>> {
+        if matched_terms.is_empty() {
+            return None;
+        }
+        Some(
+            matched_terms
+                .iter()
+                .map(|(query_term, matched)| format!("{} -> {}", query_term, matched))
+                .collect(),
+        )
+    }
+
+    #[tool(description = "Query vault notes by structured frontmatter criteria using a JSONPath expression evaluated against each note's frontmatter, e.g. \"$[?(@.status=='active')]\". A note matches when the expression selects anything. path_filter scopes the query to a subdirectory; select projects specific frontmatter keys into each result instead of returning the whole frontmatter map.")]
+    pub fn query_notes(
+        &self,
+        params: Parameters<QueryNotesRequest>,
+    ) -> Json<Vec<QueryNotesMatch>> {
+        Json(self.query_notes_impl(params.0))
+    }
+
+    pub fn query_notes_impl(
+        &self,
+        QueryNotesRequest { query, path_filter, select }: QueryNotesRequest,
+    ) -> Vec<QueryNotesMatch> {
+        let search_root = if let Some(filter) = &path_filter {
+            match self.validate_path(filter) {
+                Ok(p) => p,
+                Err(e) => {
+                    eprintln!("Invalid path filter: {}", e);
+                    return Vec::new();
+                }
             }
+        } else {
+            self.vault_root.clone()
         };
 
-        let mut related = Vec::new();
-        let mut search_terms = Vec::new();
+        let mut matches = Vec::new();
 
-        // Extract tags
-        if on.contains(&"tags".to_string()) {
-            if let Some(fm) = &frontmatter {
-                if let Some(tags) = fm.get("tags") {
-                    if let Ok(tags_vec) = serde_json::from_value::<Vec<String>>(tags.clone()) {
-                        search_terms.extend(tags_vec);
+        for entry in WalkDir::new(&search_root)
+            .into_iter()
+            .filter_map(|e| e.ok())
+        {
+            let entry_path = entry.path();
+            if !entry_path.is_file() || entry_path.extension().and_then(|s| s.to_str()) != Some("md") {
+                continue;
+            }
+
+            let Ok(content) = fs::read_to_string(entry_path) else {
+                continue;
+            };
+            let (frontmatter, _) = Self::parse_frontmatter(&content);
+            let Some(frontmatter) = frontmatter else {
+                continue;
+            };
+
+            let root = serde_json::Value::Object(frontmatter.clone());
+            let selected = match jsonpath_lib::select(&root, &query) {
+                Ok(values) => values,
+                Err(e) => {
+                    eprintln!("Invalid JSONPath query '{}': {}", query, e);
+                    return Vec::new();
+                }
+            };
+            if selected.is_empty() {
+                continue;
+            }
+
+            let Ok(rel_path) = entry_path.strip_prefix(&self.vault_root) else {
+                continue;
+            };
+
+            let values = if let Some(keys) = &select {
+                let mut projected = JsonMap::new();
+                for key in keys {
+                    if let Some(v) = frontmatter.get(key) {
+                        projected.insert(key.clone(), v.clone());
                     }
                 }
+                projected
+            } else {
+                frontmatter
+            };
+
+            matches.push(QueryNotesMatch {
+                path: rel_path.to_string_lossy().to_string(),
+                values,
+            });
+        }
+
+        matches
+    }
+
+    #[tool(description = "Tabulate the distinct values and counts of a frontmatter property across the vault, e.g. to discover what statuses or tags exist before writing a search_vault filters predicate. Array-valued properties (like tags) count each element separately. path_filter scopes the scan to a subdirectory; default_exclude_patterns from config is always subtracted. Results are sorted by descending count.")]
+    pub fn get_facet_distribution(
+        &self,
+        params: Parameters<FacetDistributionRequest>,
+    ) -> Json<FacetDistributionResult> {
+        Json(self.get_facet_distribution_impl(params.0))
+    }
+
+    pub fn get_facet_distribution_impl(
+        &self,
+        FacetDistributionRequest { property, path_filter }: FacetDistributionRequest,
+    ) -> FacetDistributionResult {
+        let search_root = if let Some(filter) = &path_filter {
+            match self.validate_path(filter) {
+                Ok(p) => p,
+                Err(e) => {
+                    eprintln!("Invalid path filter: {}", e);
+                    return FacetDistributionResult { property, values: Vec::new() };
+                }
+            }
+        } else {
+            self.vault_root.clone()
+        };
+
+        let matcher = path_matcher::build_matcher(&[], &[], &self.default_exclude_patterns);
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for entry in WalkDir::new(&search_root).into_iter().filter_map(|e| e.ok()) {
+            let entry_path = entry.path();
+            if !entry_path.is_file() || entry_path.extension().and_then(|s| s.to_str()) != Some("md") {
+                continue;
+            }
+            let Some(rel_path_str) = entry_path.strip_prefix(&self.vault_root).ok().and_then(|p| p.to_str()) else {
+                continue;
+            };
+            if !matcher.is_match(rel_path_str) {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(entry_path) else {
+                continue;
+            };
+            let (frontmatter, _) = Self::parse_frontmatter(&content);
+            let Some(frontmatter) = frontmatter else {
+                continue;
+            };
+            match frontmatter.get(&property) {
+                Some(serde_json::Value::Array(items)) => {
+                    for item in items {
+                        *counts.entry(frontmatter_filter::value_to_string(item)).or_insert(0) += 1;
+                    }
+                }
+                Some(value) if !value.is_null() => {
+                    *counts.entry(frontmatter_filter::value_to_string(value)).or_insert(0) += 1;
+                }
+                _ => {}
             }
         }
 
-        // Extract links
-        if on.contains(&"links".to_string()) {
-            let link_regex = Regex::new(r"\[\[([^\]]+)\]\]").unwrap();
-            for cap in link_regex.captures_iter(&body) {
-                if let Some(link) = cap.get(1) {
-                    search_terms.push(link.as_str().to_string());
+        let mut values: Vec<FacetValue> = counts
+            .into_iter()
+            .map(|(value, count)| FacetValue { value, count })
+            .collect();
+        values.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+
+        FacetDistributionResult { property, values }
+    }
+
+    #[tool(description = "Find notes related to a source note. Extracts tags from source note's frontmatter and wikilinks [[...]] from content. Finds other notes that: (1) have matching tags in frontmatter, or (2) have filenames matching extracted link names. 'on' parameter controls which relationships to use: 'tags' and/or 'links'. include/exclude take composable path: / rootfilesin: / glob patterns to scope candidates, on top of the configured default_exclude_patterns. Path is relative to vault root. Returns empty array if source note not found.")]
+    pub fn find_related_notes(
+        &self,
+        params: Parameters<FindRelatedNotesRequest>,
+    ) -> Json<Vec<SearchResult>> {
+        Json(self.find_related_notes_impl(params.0))
+    }
+
+    pub fn find_related_notes_impl(
+        &self,
+        FindRelatedNotesRequest { path, on, include, exclude }: FindRelatedNotesRequest,
+    ) -> Vec<SearchResult> {
+        let on = on.unwrap_or_else(|| vec!["tags".to_string(), "links".to_string()]);
+        let matcher = path_matcher::build_matcher(
+            &include.unwrap_or_default(),
+            &exclude.unwrap_or_default(),
+            &self.default_exclude_patterns,
+        );
+
+        let path_with_ext = self.ensure_md_extension(&path);
+        if let Err(e) = self.validate_path(&path_with_ext) {
+            eprintln!("Invalid path {}: {}", path_with_ext, e);
+            return Vec::new();
+        }
+
+        // Consult the persisted tag/link index instead of re-walking and
+        // re-parsing every note in the vault.
+        let index = SearchIndex::load_and_refresh(&self.vault_root);
+        let mut related = Vec::new();
+        let mut seen = std::collections::HashSet::new();
+
+        // Other notes sharing a frontmatter tag with this one.
+        if on.contains(&"tags".to_string()) {
+            for tag in index.tags_for(&path_with_ext) {
+                for other in index.notes_with_tag(&tag) {
+                    if other == path_with_ext || !seen.insert(other.clone()) {
+                        continue;
+                    }
+                    related.push(SearchResult {
+                        path: other,
+                        match_preview: Some(format!("Shared tag: {}", tag)),
+                        score: None,
+                        fuzzy_matches: None,
+                    });
                 }
             }
         }
 
-        // Find notes with matching tags or names
-        for term in search_terms {
-            for entry in WalkDir::new(&self.vault_root)
-                .into_iter()
-                .filter_map(|e| e.ok())
-            {
-                let entry_path = entry.path();
-                if !entry_path.is_file() || entry_path == full_path {
+        if on.contains(&"links".to_string()) {
+            // Notes that link to this one.
+            for other in index.backlinks(&path_with_ext) {
+                if other == path_with_ext || !seen.insert(other.clone()) {
                     continue;
                 }
+                related.push(SearchResult {
+                    path: other,
+                    match_preview: Some(format!("Links to: {}", path_with_ext)),
+                    score: None,
+                    fuzzy_matches: None,
+                });
+            }
 
-                if let Ok(rel_path) = entry_path.strip_prefix(&self.vault_root) {
-                    let rel_path_str = rel_path.to_string_lossy().to_string();
-                    
-                    // Check if filename matches
-                    if rel_path_str.contains(&term) || rel_path.file_stem()
-                        .and_then(|s| s.to_str())
-                        .map(|s| s.contains(&term))
-                        .unwrap_or(false) {
+            // Notes this one links to.
+            for link in index.outbound_links(&path_with_ext) {
+                for other in index.note_paths() {
+                    if other == path_with_ext || seen.contains(&other) {
+                        continue;
+                    }
+                    let matches = other.contains(&link)
+                        || Path::new(&other)
+                            .file_stem()
+                            .and_then(|s| s.to_str())
+                            .map(|s| s.contains(&link))
+                            .unwrap_or(false);
+                    if matches {
+                        seen.insert(other.clone());
                         related.push(SearchResult {
-                            path: rel_path_str.clone(),
-                            match_preview: Some(format!("Related via: {}", term)),
+                            path: other,
+                            match_preview: Some(format!("Related via: {}", link)),
+                            score: None,
+                            fuzzy_matches: None,
                         });
-                    } else if let Ok(file_content) = fs::read_to_string(entry_path) {
-                        // Check tags in other files
-                        let (other_fm, _) = Self::parse_frontmatter(&file_content);
-                        if let Some(other_fm) = other_fm {
-                            if let Some(other_tags) = other_fm.get("tags") {
-                                if let Ok(tags_vec) = serde_json::from_value::<Vec<String>>(other_tags.clone()) {
-                                    if tags_vec.contains(&term) {
-                                        related.push(SearchResult {
-                                            path: rel_path_str,
-                                            match_preview: Some(format!("Shared tag: {}", term)),
-                                        });
-                                    }
-                                }
-                            }
-                        }
                     }
                 }
             }
         }
 
-        Json(related)
+        related.retain(|r| matcher.is_match(&r.path));
+        related
+    }
+
+    #[tool(description = "List notes that link to the given note via a [[wikilink]], using the persisted link index (see reindex_vault to force a rescan). Path is relative to vault root, .md extension auto-added. Returns an empty array if nothing links to the note.")]
+    pub fn get_backlinks(
+        &self,
+        params: Parameters<GetBacklinksRequest>,
+    ) -> Json<Vec<String>> {
+        Json(self.get_backlinks_impl(params.0))
+    }
+
+    pub fn get_backlinks_impl(&self, GetBacklinksRequest { path }: GetBacklinksRequest) -> Vec<String> {
+        let path_with_ext = self.ensure_md_extension(&path);
+        let index = SearchIndex::load_and_refresh(&self.vault_root);
+        index.backlinks(&path_with_ext)
     }
 
     #[tool(description = "Replace text in a note. Finds target text and replaces it with new content. replace_all (default: true) controls whether to replace all occurrences or just the first. Path is relative to vault root, .md extension auto-added. Returns error if target text not found.")]
     pub fn replace_text_in_note(
         &self,
-        Parameters(ReplaceTextInNoteRequest { path, find, replace, replace_all }): Parameters<ReplaceTextInNoteRequest>,
+        params: Parameters<ReplaceTextInNoteRequest>,
     ) -> Json<OperationResult> {
+        Json(self.replace_text_in_note_impl(params.0))
+    }
+
+    pub fn replace_text_in_note_impl(
+        &self,
+        ReplaceTextInNoteRequest { path, find, replace, replace_all }: ReplaceTextInNoteRequest,
+    ) -> OperationResult {
         let path_with_ext = self.ensure_md_extension(&path);
         let full_path = match self.validate_path(&path_with_ext) {
             Ok(p) => p,
             Err(e) => {
                 eprintln!("Invalid path {}: {}", path, e);
-                return Json(OperationResult {
+                return OperationResult {
                     success: false,
                     path: None,
                     error: Some(format!("{}", e)),
                     deleted_path: None,
-                });
+                };
             }
         };
         
@@ -842,12 +1784,12 @@ impl ObsidianService {
             Ok(c) => c,
             Err(e) => {
                 eprintln!("Failed to read file {}: {}", path, e);
-                return Json(OperationResult {
+                return OperationResult {
                     success: false,
                     path: None,
                     error: Some(format!("{}", e)),
                     deleted_path: None,
-                });
+                };
             }
         };
 
@@ -857,23 +1799,23 @@ impl ObsidianService {
             Ok(re) => re,
             Err(e) => {
                 eprintln!("Invalid regex pattern: {}", e);
-                return Json(OperationResult {
+                return OperationResult {
                     success: false,
                     path: None,
                     error: Some(format!("Invalid pattern: {}", e)),
                     deleted_path: None,
-                });
+                };
             }
         };
 
         if !find_regex.is_match(&file_content) {
             eprintln!("Target text not found in file");
-            return Json(OperationResult {
+            return OperationResult {
                 success: false,
                 path: None,
                 error: Some("Target text not found in file".to_string()),
                 deleted_path: None,
-            });
+            };
         }
 
         let new_content = if replace_all {
@@ -882,21 +1824,22 @@ impl ObsidianService {
             find_regex.replace(&file_content, &normalized_replace).to_string()
         };
 
-        match fs::write(&full_path, new_content) {
-            Ok(_) => Json(OperationResult {
+        note_history::snapshot(&self.vault_root, &path_with_ext, &full_path);
+        match note_history::atomic_write(&full_path, &new_content) {
+            Ok(_) => OperationResult {
                 success: true,
                 path: Some(path_with_ext),
                 error: None,
                 deleted_path: None,
-            }),
+            },
             Err(e) => {
                 eprintln!("Failed to write file: {}", e);
-                Json(OperationResult {
+                OperationResult {
                     success: false,
                     path: None,
                     error: Some(format!("{}", e)),
                     deleted_path: None,
-                })
+                }
             }
         }
     }
@@ -904,19 +1847,26 @@ impl ObsidianService {
     #[tool(description = "Append text to a specific markdown section. section_header must include # markers (e.g., '## End day') and must match exactly (level and text). Appends content before the next header of the same or higher level (or at end of file). Returns error if: header level not specified, section not found, level mismatch, or multiple sections match. Path is relative to vault root, .md extension auto-added.")]
     pub fn append_to_section(
         &self,
-        Parameters(AppendToSectionRequest { path, section_header, text_to_append }): Parameters<AppendToSectionRequest>,
+        params: Parameters<AppendToSectionRequest>,
     ) -> Json<OperationResult> {
+        Json(self.append_to_section_impl(params.0))
+    }
+
+    pub fn append_to_section_impl(
+        &self,
+        AppendToSectionRequest { path, section_header, text_to_append }: AppendToSectionRequest,
+    ) -> OperationResult {
         let path_with_ext = self.ensure_md_extension(&path);
         let full_path = match self.validate_path(&path_with_ext) {
             Ok(p) => p,
             Err(e) => {
                 eprintln!("Invalid path {}: {}", path, e);
-                return Json(OperationResult {
+                return OperationResult {
                     success: false,
                     path: None,
                     error: Some(format!("{}", e)),
                     deleted_path: None,
-                });
+                };
             }
         };
 
@@ -924,12 +1874,12 @@ impl ObsidianService {
             Ok(c) => c,
             Err(e) => {
                 eprintln!("Failed to read file {}: {}", path, e);
-                return Json(OperationResult {
+                return OperationResult {
                     success: false,
                     path: None,
                     error: Some(format!("{}", e)),
                     deleted_path: None,
-                });
+                };
             }
         };
 
@@ -937,12 +1887,12 @@ impl ObsidianService {
         let (target_level, target_text) = match Self::parse_section_header(&section_header) {
             Ok((level, text)) => (level, text),
             Err(e) => {
-                return Json(OperationResult {
+                return OperationResult {
                     success: false,
                     path: None,
                     error: Some(e),
                     deleted_path: None,
-                });
+                };
             }
         };
 
@@ -964,22 +1914,22 @@ impl ObsidianService {
                     let header_info: Vec<String> = all_headers.iter()
                         .map(|(line, level, _)| format!("'{}' at line {}", "#".repeat(*level as usize), line + 1))
                         .collect();
-                    return Json(OperationResult {
+                    return OperationResult {
                         success: false,
                         path: None,
                         error: Some(format!("Section not found. Header level mismatch. Looking for '{} {}' but found {}",
                             "#".repeat(target_level as usize), target_text, header_info.join(", "))),
                         deleted_path: None,
-                    });
+                    };
                 }
                 
-                return Json(OperationResult {
+                return OperationResult {
                     success: false,
                     path: None,
                     error: Some(format!("Section not found. No header matching '{} {}' found in file",
                         "#".repeat(target_level as usize), target_text)),
                     deleted_path: None,
-                });
+                };
             }
             1 => {
                 // Found exactly one match - proceed
@@ -988,13 +1938,13 @@ impl ObsidianService {
                 let line_numbers: Vec<String> = matches.iter()
                     .map(|(line, _, _)| (line + 1).to_string())
                     .collect();
-                return Json(OperationResult {
+                return OperationResult {
                     success: false,
                     path: None,
                     error: Some(format!("Multiple sections found. Found {} headers matching '{} {}' at lines {}. Use replace_text_in_note for precise targeting.",
                         n, "#".repeat(target_level as usize), target_text, line_numbers.join(", "))),
                     deleted_path: None,
-                });
+                };
             }
         }
 
@@ -1040,22 +1990,23 @@ impl ObsidianService {
         }
         
         let new_content = new_lines.join("\n");
-        
-        match fs::write(&full_path, new_content) {
-            Ok(_) => Json(OperationResult {
+
+        note_history::snapshot(&self.vault_root, &path_with_ext, &full_path);
+        match note_history::atomic_write(&full_path, &new_content) {
+            Ok(_) => OperationResult {
                 success: true,
                 path: Some(path_with_ext),
                 error: None,
                 deleted_path: None,
-            }),
+            },
             Err(e) => {
                 eprintln!("Failed to write file: {}", e);
-                Json(OperationResult {
+                OperationResult {
                     success: false,
                     path: None,
                     error: Some(format!("{}", e)),
                     deleted_path: None,
-                })
+                }
             }
         }
     }
@@ -1063,19 +2014,26 @@ impl ObsidianService {
     #[tool(description = "Update frontmatter properties (Obsidian properties) in a note. Updates/adds properties from 'properties' map and removes properties listed in 'remove'. Does not modify note content body. Creates frontmatter if it doesn't exist. Path is relative to vault root, .md extension auto-added.")]
     pub fn update_note_properties(
         &self,
-        Parameters(UpdateNotePropertiesRequest { path, properties, remove }): Parameters<UpdateNotePropertiesRequest>,
+        params: Parameters<UpdateNotePropertiesRequest>,
     ) -> Json<OperationResult> {
+        Json(self.update_note_properties_impl(params.0))
+    }
+
+    pub fn update_note_properties_impl(
+        &self,
+        UpdateNotePropertiesRequest { path, properties, remove }: UpdateNotePropertiesRequest,
+    ) -> OperationResult {
         let path_with_ext = self.ensure_md_extension(&path);
         let full_path = match self.validate_path(&path_with_ext) {
             Ok(p) => p,
             Err(e) => {
                 eprintln!("Invalid path {}: {}", path, e);
-                return Json(OperationResult {
+                return OperationResult {
                     success: false,
                     path: None,
                     error: Some(format!("{}", e)),
                     deleted_path: None,
-                });
+                };
             }
         };
 
@@ -1083,12 +2041,12 @@ impl ObsidianService {
             Ok(c) => c,
             Err(e) => {
                 eprintln!("Failed to read file {}: {}", path, e);
-                return Json(OperationResult {
+                return OperationResult {
                     success: false,
                     path: None,
                     error: Some(format!("{}", e)),
                     deleted_path: None,
-                });
+                };
             }
         };
 
@@ -1115,120 +2073,490 @@ impl ObsidianService {
         // Format with updated frontmatter
         let new_content = Self::format_with_frontmatter(&body, Some(&fm));
 
-        match fs::write(&full_path, new_content) {
-            Ok(_) => Json(OperationResult {
+        note_history::snapshot(&self.vault_root, &path_with_ext, &full_path);
+        match note_history::atomic_write(&full_path, &new_content) {
+            Ok(_) => OperationResult {
                 success: true,
                 path: Some(path_with_ext),
                 error: None,
                 deleted_path: None,
-            }),
+            },
             Err(e) => {
                 eprintln!("Failed to write file: {}", e);
-                Json(OperationResult {
+                OperationResult {
                     success: false,
                     path: None,
                     error: Some(format!("{}", e)),
                     deleted_path: None,
-                })
+                }
+            }
+        }
+    }
+
+    #[tool(description = "Parse and return a note's leading YAML frontmatter block as JSON. Path is relative to vault root, .md extension auto-added. frontmatter is null if the note has no --- block; error is set if the note can't be read or the block isn't valid YAML.")]
+    pub fn get_note_frontmatter(
+        &self,
+        params: Parameters<GetNoteFrontmatterRequest>,
+    ) -> Json<NoteFrontmatterResult> {
+        Json(self.get_note_frontmatter_impl(params.0))
+    }
+
+    pub fn get_note_frontmatter_impl(
+        &self,
+        GetNoteFrontmatterRequest { path }: GetNoteFrontmatterRequest,
+    ) -> NoteFrontmatterResult {
+        let path_with_ext = self.ensure_md_extension(&path);
+        let full_path = match self.validate_path(&path_with_ext) {
+            Ok(p) => p,
+            Err(e) => {
+                return NoteFrontmatterResult {
+                    path: path_with_ext,
+                    frontmatter: None,
+                    error: Some(format!("{}", e)),
+                };
+            }
+        };
+
+        let content = match fs::read_to_string(&full_path) {
+            Ok(c) => c,
+            Err(e) => {
+                return NoteFrontmatterResult {
+                    path: path_with_ext,
+                    frontmatter: None,
+                    error: Some(format!("{}", e)),
+                };
             }
+        };
+
+        match Self::try_parse_frontmatter(&content) {
+            Ok((frontmatter, _body)) => NoteFrontmatterResult { path: path_with_ext, frontmatter, error: None },
+            Err(e) => NoteFrontmatterResult { path: path_with_ext, frontmatter: None, error: Some(e) },
         }
     }
 
-    #[tool(description = "Create note from template with variable substitution. Template path: if starts with '/' or contains ':', treated as absolute path relative to vault root; otherwise relative to templates directory (paths from list_notes_templates can be used directly). Destination path SHOULD include .md extension. Replaces {{variable}} placeholders in template with values from variables map. Creates parent directories if needed.")]
+    #[tool(description = "Merge a JSON object into a note's existing frontmatter, preserving the body and any keys not mentioned. Path is relative to vault root, .md extension auto-added. Creates a frontmatter block if the note doesn't have one.")]
+    pub fn update_note_frontmatter(
+        &self,
+        params: Parameters<UpdateNoteFrontmatterRequest>,
+    ) -> Json<OperationResult> {
+        Json(self.update_note_frontmatter_impl(params.0))
+    }
+
+    pub fn update_note_frontmatter_impl(
+        &self,
+        UpdateNoteFrontmatterRequest { path, frontmatter }: UpdateNoteFrontmatterRequest,
+    ) -> OperationResult {
+        self.update_note_properties_impl(UpdateNotePropertiesRequest {
+            path,
+            properties: Some(frontmatter.into_iter().collect()),
+            remove: None,
+        })
+    }
+
+    #[tool(description = "Create note from template with handlebars rendering. Template path: if starts with '/' or contains ':', treated as absolute path relative to vault root; otherwise relative to templates directory (paths from list_notes_templates can be used directly). Destination path SHOULD include .md extension. Supports {{include: path}} directives (spliced from the templates directory, recursively, with cycle protection) and {{date:FORMAT| OFFSET| BASE}} (a moment.js-style FORMAT such as YYYY-MM-DD, an optional offset such as +1d, and an optional base date) as preprocessing passes, then renders the result with handlebars in strict mode: {{#if}}/{{#each}}/{{#unless}} blocks, nested variable paths like {{author.name}}, and partials registered from every .md file in the templates directory (by path relative to the templates directory, extension stripped). 'variables' is a JSON object (supports nesting and arrays); 'date'/'time'/'title'/'uuid' are filled in automatically when not supplied (date_format/time_format control the strftime format of the built-in 'date'/'time', defaulting to ISO-8601). A variable referenced in the template but missing from 'variables' is reported as an error rather than left as literal {{...}}. frontmatter_strategy controls whether 'variables' is also merged into the rendered template's own frontmatter block ('auto' default, 'always', or 'never'); a malformed frontmatter block is reported as an error. Creates parent directories if needed.")]
     pub fn create_note_from_template(
         &self,
-        Parameters(CreateNoteFromTemplateRequest { path, template_path, variables }): Parameters<CreateNoteFromTemplateRequest>,
+        params: Parameters<CreateNoteFromTemplateRequest>,
     ) -> Json<OperationResult> {
-        let template_full = if template_path.starts_with('/') || template_path.contains(':') {
-            // Absolute path
-            match self.validate_path(&template_path) {
-                Ok(p) => p,
-                Err(e) => {
-                    eprintln!("Invalid template path {}: {}", template_path, e);
-                    return Json(OperationResult {
+        Json(self.create_note_from_template_impl(params.0))
+    }
+
+    pub fn create_note_from_template_impl(
+        &self,
+        CreateNoteFromTemplateRequest { path, template_path, variables, date_format, time_format, frontmatter_strategy }: CreateNoteFromTemplateRequest,
+    ) -> OperationResult {
+        let templates_dir = self.templates_path.as_ref()
+            .map(|p| self.vault_root.join(p))
+            .unwrap_or_else(|| self.vault_root.join("templates"));
+
+        let template_full = match self.resolve_template_path(&template_path, &templates_dir) {
+            Ok(p) => p,
+            Err(e) => {
+                eprintln!("{}", e);
+                return OperationResult {
                     success: false,
                     path: None,
-                    error: Some(format!("{}", e)),
+                    error: Some(e),
                     deleted_path: None,
-                });
-                }
+                };
             }
-        } else {
-            // Relative to templates directory
-            let templates_dir = self.templates_path.as_ref()
-                .map(|p| self.vault_root.join(p))
-                .unwrap_or_else(|| self.vault_root.join("templates"));
-            templates_dir.join(&template_path)
         };
 
-        if !template_full.exists() {
-            eprintln!("Template file not found: {}", template_path);
-            return Json(OperationResult {
-                success: false,
-                path: None,
-                error: Some(format!("Template file not found: {}", template_path)),
-                deleted_path: None,
-            });
-        }
-
         let template_content = match fs::read_to_string(&template_full) {
             Ok(c) => c,
             Err(e) => {
                 eprintln!("Failed to read template: {}", e);
-                return Json(OperationResult {
+                return OperationResult {
                     success: false,
                     path: None,
                     error: Some(format!("{}", e)),
                     deleted_path: None,
-                });
+                };
             }
         };
-        
-        let variables = variables.unwrap_or_default();
 
-        // Replace {{variable}} placeholders
-        let mut final_content = template_content;
-        for (key, value) in variables {
-            let placeholder = format!("{{{{{}}}}}", key);
-            final_content = final_content.replace(&placeholder, &value);
+        let template_content = match Self::resolve_template_includes(&template_content, &templates_dir, 0) {
+            Ok(c) => c,
+            Err(e) => {
+                eprintln!("Failed to resolve template includes: {}", e);
+                return OperationResult {
+                    success: false,
+                    path: None,
+                    error: Some(e),
+                    deleted_path: None,
+                };
+            }
+        };
+
+        // Ensure .md extension up front so the 'title' built-in can be
+        // derived from the real destination filename.
+        let final_path = self.ensure_md_extension(&path);
+        let mut variables = match variables {
+            Some(serde_json::Value::Object(map)) => map,
+            Some(_) => {
+                return OperationResult {
+                    success: false,
+                    path: None,
+                    error: Some("variables must be a JSON object".to_string()),
+                    deleted_path: None,
+                };
+            }
+            None => JsonMap::new(),
+        };
+        let now = Local::now();
+        variables.entry("title".to_string()).or_insert_with(|| {
+            Path::new(&final_path)
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or(&final_path)
+                .to_string()
+                .into()
+        });
+        let date_format = date_format.as_deref().unwrap_or("%Y-%m-%d");
+        let time_format = time_format.as_deref().unwrap_or("%H:%M:%S");
+        variables.entry("date".to_string()).or_insert_with(|| now.format(date_format).to_string().into());
+        variables.entry("time".to_string()).or_insert_with(|| now.format(time_format).to_string().into());
+        variables.entry("uuid".to_string()).or_insert_with(|| Uuid::new_v4().to_string().into());
+
+        // {{date:FORMAT| OFFSET| BASE}} (moment.js-style format, optional
+        // offset and base date) and {{2 + 3}} numeric expressions, evaluated
+        // before the handlebars pass since neither is valid handlebars syntax.
+        let template_vars: HashMap<String, String> = variables
+            .iter()
+            .filter_map(|(k, v)| Self::json_scalar_to_string(v).map(|s| (k.clone(), s)))
+            .collect();
+        let template_content = TemplateProcessor::process(&template_content, &template_vars);
+
+        // Render with handlebars: {{variable}}, {{#if}}/{{#each}}/nested
+        // paths, and partials registered from every template in the
+        // templates directory. Strict mode turns a variable referenced in
+        // the template but missing from `variables` into a render error.
+        let mut handlebars = handlebars::Handlebars::new();
+        handlebars.set_strict_mode(true);
+        Self::register_template_partials(&mut handlebars, &templates_dir);
+
+        let mut final_content = match handlebars.render_template(&template_content, &variables) {
+            Ok(content) => content,
+            Err(e) => {
+                return OperationResult {
+                    success: false,
+                    path: None,
+                    error: Some(format!("Template render error: {}", e)),
+                    deleted_path: None,
+                };
+            }
+        };
+
+        // Merge `variables` into the rendered template's own frontmatter
+        // block, per frontmatter_strategy, instead of relying solely on
+        // {{...}} substitution inside the frontmatter YAML.
+        let strategy = FrontmatterStrategy::parse(frontmatter_strategy.as_deref());
+        if strategy != FrontmatterStrategy::Never {
+            let (template_fm, body) = match Self::try_parse_frontmatter(&final_content) {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    return OperationResult {
+                        success: false,
+                        path: None,
+                        error: Some(e),
+                        deleted_path: None,
+                    };
+                }
+            };
+            let should_merge = template_fm.is_some() || strategy == FrontmatterStrategy::Always;
+            if should_merge {
+                let mut merged_fm = template_fm.unwrap_or_default();
+                merged_fm.extend(variables.clone());
+                final_content = Self::format_with_frontmatter(&body, Some(&merged_fm));
+            }
         }
 
+        // Split back into body/frontmatter so default_frontmatter merging
+        // (run via the shared postprocessor pipeline) has somewhere to land,
+        // same as create_or_update_note_impl.
+        let (fm, mut body) = match Self::try_parse_frontmatter(&final_content) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                return OperationResult {
+                    success: false,
+                    path: None,
+                    error: Some(e),
+                    deleted_path: None,
+                };
+            }
+        };
+        let mut ctx = postprocessor::NoteContext { path: final_path.clone(), frontmatter: fm };
+        if !postprocessor::run_pipeline(&self.postprocessors, &mut ctx, &mut body) {
+            return OperationResult {
+                success: false,
+                path: None,
+                error: Some("Note write skipped by postprocessor pipeline".to_string()),
+                deleted_path: None,
+            };
+        }
+        let final_content = Self::format_with_frontmatter(&body, ctx.frontmatter.as_ref());
+
         // Write to destination (ensure .md extension)
-        let final_path = self.ensure_md_extension(&path);
         let dest_path = self.vault_root.join(&final_path);
         if let Some(parent) = dest_path.parent() {
             if let Err(e) = fs::create_dir_all(parent) {
                 eprintln!("Failed to create directory: {}", e);
-                return Json(OperationResult {
+                return OperationResult {
                     success: false,
                     path: None,
                     error: Some(format!("{}", e)),
                     deleted_path: None,
-                });
+                };
             }
         }
 
+        note_history::snapshot(&self.vault_root, &final_path, &dest_path);
         match fs::write(&dest_path, final_content) {
-            Ok(_) => Json(OperationResult {
+            Ok(_) => OperationResult {
                 success: true,
                 path: Some(final_path),
                 error: None,
                 deleted_path: None,
-            }),
+            },
             Err(e) => {
                 eprintln!("Failed to write file: {}", e);
-                Json(OperationResult {
+                OperationResult {
                     success: false,
                     path: None,
                     error: Some(format!("{}", e)),
                     deleted_path: None,
-                })
+                }
             }
         }
     }
 
-    #[tool(description = "List all .md template files in templates directory. Returns paths relative to templates directory (can be used directly with create_note_from_template). Templates directory is configured templates_path or 'templates/' in vault root. Returns template file paths, names, and sizes. Returns empty array if templates directory doesn't exist.")]
-    pub fn list_notes_templates(&self) -> Json<Vec<DirectoryItem>> {
+    /// Resolves a `template_path` to a file on disk: if it starts with '/' or
+    /// contains ':', it's treated as absolute relative to vault root;
+    /// otherwise relative to `templates_dir`. Shared by
+    /// create_note_from_template_impl and describe_note_template_impl.
+    fn resolve_template_path(&self, template_path: &str, templates_dir: &Path) -> Result<PathBuf, String> {
+        let template_full = if template_path.starts_with('/') || template_path.contains(':') {
+            self.validate_path(template_path).map_err(|e| format!("Invalid template path {}: {}", template_path, e))?
+        } else {
+            templates_dir.join(template_path)
+        };
+
+        if !template_full.exists() {
+            return Err(format!("Template file not found: {}", template_path));
+        }
+
+        Ok(template_full)
+    }
+
+    /// Splice `{{include: path}}` directives with the contents of another
+    /// template file (`path` relative to `templates_dir`), recursively, up
+    /// to `MAX_TEMPLATE_INCLUDE_DEPTH` to catch include cycles.
+    fn resolve_template_includes(content: &str, templates_dir: &Path, depth: usize) -> Result<String, String> {
+        if depth >= MAX_TEMPLATE_INCLUDE_DEPTH {
+            return Err(format!(
+                "Template include depth exceeded {} levels (possible include cycle)",
+                MAX_TEMPLATE_INCLUDE_DEPTH
+            ));
+        }
+
+        let include_regex = Regex::new(r"\{\{include:\s*([^}]+?)\s*\}\}").unwrap();
+        let mut result = String::with_capacity(content.len());
+        let mut last_end = 0;
+
+        for cap in include_regex.captures_iter(content) {
+            let whole = cap.get(0).unwrap();
+            let include_path = cap.get(1).unwrap().as_str();
+
+            let included_full = templates_dir.join(include_path);
+            let included_content = fs::read_to_string(&included_full)
+                .map_err(|e| format!("Failed to include '{}': {}", include_path, e))?;
+            let resolved = Self::resolve_template_includes(&included_content, templates_dir, depth + 1)?;
+
+            result.push_str(&content[last_end..whole.start()]);
+            result.push_str(&resolved);
+            last_end = whole.end();
+        }
+        result.push_str(&content[last_end..]);
+
+        Ok(result)
+    }
+
+    /// Renders a scalar JSON value (string/number/bool) as a plain string for
+    /// `TemplateProcessor`, which only understands flat string variables.
+    /// Objects, arrays, and null have no sensible flat representation and are
+    /// left for handlebars to resolve via its own nested-path lookup.
+    fn json_scalar_to_string(value: &serde_json::Value) -> Option<String> {
+        match value {
+            serde_json::Value::String(s) => Some(s.clone()),
+            serde_json::Value::Number(n) => Some(n.to_string()),
+            serde_json::Value::Bool(b) => Some(b.to_string()),
+            serde_json::Value::Null | serde_json::Value::Object(_) | serde_json::Value::Array(_) => None,
+        }
+    }
+
+    /// Registers every `.md` file under `templates_dir` as a handlebars
+    /// partial, keyed by its path relative to `templates_dir` with the `.md`
+    /// extension stripped, so templates can `{{> partials/header}}`.
+    fn register_template_partials(handlebars: &mut handlebars::Handlebars, templates_dir: &Path) {
+        if !templates_dir.is_dir() {
+            return;
+        }
+        for entry in WalkDir::new(templates_dir).into_iter().filter_map(|e| e.ok()) {
+            let entry_path = entry.path();
+            if entry_path.extension().and_then(|e| e.to_str()) != Some("md") {
+                continue;
+            }
+            let Ok(rel_path) = entry_path.strip_prefix(templates_dir) else { continue };
+            let Some(name) = rel_path.to_str() else { continue };
+            let name = name.trim_end_matches(".md");
+            if let Ok(content) = fs::read_to_string(entry_path) {
+                let _ = handlebars.register_partial(name, content);
+            }
+        }
+    }
+
+    /// Names filled in automatically by create_note_from_template when not
+    /// supplied, so they're never reported as variables a caller must pass.
+    const BUILTIN_TEMPLATE_VARS: [&'static str; 4] = ["title", "date", "time", "uuid"];
+
+    /// Scans a template body for `{{variable}}`/`{{author.name}}` references
+    /// and the argument of `{{#if}}`/`{{#each}}`/`{{#unless}}`/`{{#with}}`
+    /// blocks, skipping closing tags, partials, comments, and the
+    /// `{{include: ...}}`/`{{date:FORMAT}}` preprocessing directives (which
+    /// aren't handlebars variables). Built-ins and duplicates are excluded.
+    fn discover_template_placeholders(content: &str) -> Vec<String> {
+        let placeholder_regex = Regex::new(r"\{\{\{?([^{}]+?)\}?\}\}").unwrap();
+        let mut names: Vec<String> = Vec::new();
+
+        for cap in placeholder_regex.captures_iter(content) {
+            let raw = cap[1].trim();
+            if raw.is_empty()
+                || raw.starts_with('/')
+                || raw.starts_with('>')
+                || raw.starts_with('!')
+                || raw.starts_with("include:")
+                || raw.starts_with("date:")
+                || raw == "else"
+                || raw.starts_with("else ")
+            {
+                continue;
+            }
+
+            let name = if let Some(rest) = raw.strip_prefix('#').or_else(|| raw.strip_prefix('^')) {
+                let mut parts = rest.split_whitespace();
+                let keyword = parts.next().unwrap_or("");
+                if !matches!(keyword, "if" | "unless" | "each" | "with") {
+                    continue;
+                }
+                match parts.next() {
+                    Some(arg) => arg,
+                    None => continue,
+                }
+            } else if raw.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '.') {
+                raw
+            } else {
+                continue;
+            };
+
+            if Self::BUILTIN_TEMPLATE_VARS.contains(&name) || names.iter().any(|n| n == name) {
+                continue;
+            }
+            names.push(name.to_string());
+        }
+
+        names
+    }
+
+    /// Parses an optional `variables:` list from a template's own leading
+    /// frontmatter, declaring descriptions/defaults for placeholders (e.g. a
+    /// plain list of names, or a list of `{name, description, default,
+    /// required}` objects). Takes priority over placeholder-scan results.
+    fn template_variable_overrides(frontmatter: &JsonMap<String, serde_json::Value>) -> HashMap<String, TemplateVariable> {
+        let mut overrides = HashMap::new();
+        let Some(serde_json::Value::Array(entries)) = frontmatter.get("variables") else {
+            return overrides;
+        };
+        for entry in entries {
+            let variable = match entry {
+                serde_json::Value::String(name) => TemplateVariable {
+                    name: name.clone(),
+                    required: true,
+                    description: None,
+                    default: None,
+                },
+                serde_json::Value::Object(obj) => {
+                    let Some(name) = obj.get("name").and_then(|v| v.as_str()) else { continue };
+                    let default = obj.get("default").cloned();
+                    TemplateVariable {
+                        name: name.to_string(),
+                        required: obj.get("required").and_then(|v| v.as_bool()).unwrap_or(default.is_none()),
+                        description: obj.get("description").and_then(|v| v.as_str()).map(String::from),
+                        default,
+                    }
+                }
+                _ => continue,
+            };
+            overrides.insert(variable.name.clone(), variable);
+        }
+        overrides
+    }
+
+    /// Builds a template's manifest: declared/discovered variables, layered
+    /// so frontmatter `variables:` declarations override placeholder-scan
+    /// defaults (required=true, no description/default) for the same name.
+    fn build_template_manifest(rel_path: String, name: String, size: Option<u64>, content: &str) -> TemplateManifest {
+        let (frontmatter, _) = Self::parse_frontmatter(content);
+        let mut overrides = frontmatter
+            .as_ref()
+            .map(Self::template_variable_overrides)
+            .unwrap_or_default();
+
+        let mut variables = Vec::new();
+        for placeholder in Self::discover_template_placeholders(content) {
+            if let Some(variable) = overrides.remove(&placeholder) {
+                variables.push(variable);
+            } else {
+                variables.push(TemplateVariable {
+                    name: placeholder,
+                    required: true,
+                    description: None,
+                    default: None,
+                });
+            }
+        }
+        // Anything left in `overrides` was declared in frontmatter but never
+        // referenced in the body; still report it, it's still a valid input.
+        variables.extend(overrides.into_values());
+
+        TemplateManifest { path: rel_path, name, size, variables }
+    }
+
+    #[tool(description = "List all .md template files in templates directory, each with its required/optional {{variables}} (discovered from {{placeholder}} references in the template body and any variables: list in its own frontmatter). Returns paths relative to templates directory (can be used directly with create_note_from_template). Templates directory is configured templates_path or 'templates/' in vault root. Returns empty array if templates directory doesn't exist.")]
+    pub fn list_notes_templates(&self) -> Json<Vec<TemplateManifest>> {
+        Json(self.list_notes_templates_impl())
+    }
+
+    pub fn list_notes_templates_impl(&self) -> Vec<TemplateManifest> {
         let templates_dir = self.templates_path.as_ref()
             .map(|p| self.vault_root.join(p))
             .unwrap_or_else(|| self.vault_root.join("templates"));
@@ -1247,18 +2575,175 @@ impl ObsidianService {
                     // This allows direct use with create_note_from_template
                     if let Ok(rel_path) = entry_path.strip_prefix(&templates_dir) {
                         let metadata = entry.metadata().ok();
-                        items.push(DirectoryItem {
-                            path: rel_path.to_string_lossy().to_string(),
-                            name: entry_path.file_name().unwrap_or_default().to_string_lossy().to_string(),
-                            is_file: true,
-                            size: metadata.and_then(|m| Some(m.len())),
-                        });
+                        let content = fs::read_to_string(&entry_path).unwrap_or_default();
+                        items.push(Self::build_template_manifest(
+                            rel_path.to_string_lossy().to_string(),
+                            entry_path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+                            metadata.map(|m| m.len()),
+                            &content,
+                        ));
                     }
                 }
             }
         }
 
-        Json(items)
+        items
+    }
+
+    #[tool(description = "Describe a single template: its required/optional {{variables}}, discovered from {{placeholder}} references in the template body and any variables: list in its own frontmatter. Accepts the same template_path rules as create_note_from_template (absolute if it starts with '/' or contains ':', otherwise relative to templates directory).")]
+    pub fn describe_note_template(
+        &self,
+        params: Parameters<DescribeNoteTemplateRequest>,
+    ) -> Json<DescribeNoteTemplateResult> {
+        Json(self.describe_note_template_impl(params.0))
+    }
+
+    pub fn describe_note_template_impl(
+        &self,
+        DescribeNoteTemplateRequest { template_path }: DescribeNoteTemplateRequest,
+    ) -> DescribeNoteTemplateResult {
+        let templates_dir = self.templates_path.as_ref()
+            .map(|p| self.vault_root.join(p))
+            .unwrap_or_else(|| self.vault_root.join("templates"));
+
+        let template_full = match self.resolve_template_path(&template_path, &templates_dir) {
+            Ok(p) => p,
+            Err(e) => return DescribeNoteTemplateResult { manifest: None, error: Some(e) },
+        };
+
+        let content = match fs::read_to_string(&template_full) {
+            Ok(c) => c,
+            Err(e) => return DescribeNoteTemplateResult { manifest: None, error: Some(format!("{}", e)) },
+        };
+
+        let size = fs::metadata(&template_full).ok().map(|m| m.len());
+        let name = template_full.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let manifest = Self::build_template_manifest(template_path, name, size, &content);
+
+        DescribeNoteTemplateResult { manifest: Some(manifest), error: None }
+    }
+
+    #[tool(description = "Rebuild or refresh the on-disk search index used by search_vault's content scope. By default only notes whose mtime changed since the last index are re-parsed; pass full=true to discard the index and rescan the whole vault.")]
+    pub fn reindex_vault(
+        &self,
+        params: Parameters<ReindexVaultRequest>,
+    ) -> Json<ReindexResult> {
+        Json(self.reindex_vault_impl(params.0))
+    }
+
+    pub fn reindex_vault_impl(
+        &self,
+        ReindexVaultRequest { full }: ReindexVaultRequest,
+    ) -> ReindexResult {
+        let index = if full.unwrap_or(false) {
+            SearchIndex::rebuild(&self.vault_root)
+        } else {
+            SearchIndex::load_and_refresh(&self.vault_root)
+        };
+        ReindexResult {
+            documents_indexed: index.document_count(),
+            terms: index.term_count(),
+        }
+    }
+
+    #[tool(description = "Stage all vault changes, commit (auto-generated or supplied message), and push to the configured git remote/branch. Returns the resulting HEAD, number of files committed, and whether the push succeeded.")]
+    pub fn push_vault(
+        &self,
+        params: Parameters<PushVaultRequest>,
+    ) -> Json<GitSyncResult> {
+        Json(self.push_vault_impl(params.0))
+    }
+
+    pub fn push_vault_impl(&self, PushVaultRequest { message }: PushVaultRequest) -> GitSyncResult {
+        GitSync::new(&self.vault_root, self.git_remote.as_deref(), self.git_branch.as_deref()).push(message)
+    }
+
+    #[tool(description = "Fetch and merge the configured git remote/branch into the vault (fast-forward or merge commit). Returns the resulting HEAD, number of files changed, and whether the merge produced conflicts.")]
+    pub fn pull_vault(
+        &self,
+        params: Parameters<PullVaultRequest>,
+    ) -> Json<GitSyncResult> {
+        Json(self.pull_vault_impl(params.0))
+    }
+
+    pub fn pull_vault_impl(&self, PullVaultRequest {}: PullVaultRequest) -> GitSyncResult {
+        GitSync::new(&self.vault_root, self.git_remote.as_deref(), self.git_branch.as_deref()).pull()
+    }
+
+    #[tool(description = "Export the entire vault into a versioned, gzipped-tar archive. The archive includes a .mcp_dump_metadata.json entry recording the dump format version, crate version, and creation time, plus a .mcp_dump_pathconfig.json entry recording the configured daily/weekly/monthly/templates paths, both written before the vault's own files so a streaming consumer can read them first. output_path defaults to 'vault-dump-<timestamp>.tar.gz' in the current directory.")]
+    pub fn dump_vault(
+        &self,
+        params: Parameters<DumpVaultRequest>,
+    ) -> Json<DumpResult> {
+        Json(self.dump_vault_impl(params.0))
+    }
+
+    pub fn dump_vault_impl(&self, DumpVaultRequest { output_path }: DumpVaultRequest) -> DumpResult {
+        let output_path = output_path.unwrap_or_else(|| {
+            format!("vault-dump-{}.tar.gz", Local::now().format("%Y-%m-%d_%H-%M-%S"))
+        });
+        let path_config = vault_dump::DumpPathConfig {
+            daily_notes_path: self.daily_notes_path.as_ref().map(|p| p.to_string_lossy().to_string()),
+            weekly_notes_path: self.weekly_notes_path.as_ref().map(|p| p.to_string_lossy().to_string()),
+            monthly_notes_path: self.monthly_notes_path.as_ref().map(|p| p.to_string_lossy().to_string()),
+            templates_path: self.templates_path.as_ref().map(|p| p.to_string_lossy().to_string()),
+        };
+        vault_dump::create_dump(&self.vault_root, &PathBuf::from(output_path), &path_config)
+    }
+
+    #[tool(description = "Restore a vault from an archive created by dump_vault. Refuses an archive whose format_version doesn't match this build's. By default existing files are left untouched; set overwrite=true to replace them with the archive's contents. Files present only in the vault (not in the archive) are left as-is. Returns the archive's path_config sidecar (daily/weekly/monthly/templates paths), if present, for the caller to apply if desired.")]
+    pub fn load_vault(
+        &self,
+        params: Parameters<LoadVaultRequest>,
+    ) -> Json<LoadResult> {
+        Json(self.load_vault_impl(params.0))
+    }
+
+    pub fn load_vault_impl(&self, LoadVaultRequest { input_path, overwrite }: LoadVaultRequest) -> LoadResult {
+        vault_dump::load_dump(&self.vault_root, &PathBuf::from(input_path), overwrite.unwrap_or(false))
+    }
+
+    #[tool(description = "Convert a note's Obsidian-flavored markdown into portable standard markdown. [[target#section|label]] wikilinks are rewritten to relative markdown links; ![[target]] embeds are inlined with the referenced note's body (recursion guarded against cycles, ~10 levels deep). Unresolvable targets are left as literal text. Path is relative to vault root, .md extension auto-added.")]
+    pub fn export_note(
+        &self,
+        params: Parameters<ExportNoteRequest>,
+    ) -> Json<export::ExportNoteResult> {
+        Json(self.export_note_impl(params.0))
+    }
+
+    pub fn export_note_impl(&self, ExportNoteRequest { path }: ExportNoteRequest) -> export::ExportNoteResult {
+        let path_with_ext = self.ensure_md_extension(&path);
+        let full_path = match self.validate_path(&path_with_ext) {
+            Ok(p) => p,
+            Err(e) => {
+                return export::ExportNoteResult { path: path_with_ext, content: None, error: Some(format!("{}", e)) };
+            }
+        };
+
+        let content = match fs::read_to_string(&full_path) {
+            Ok(c) => c,
+            Err(e) => {
+                return export::ExportNoteResult { path: path_with_ext, content: None, error: Some(format!("{}", e)) };
+            }
+        };
+
+        let matcher = path_matcher::build_matcher(&[], &[], &self.default_exclude_patterns);
+        let index = export::build_filename_index(&self.vault_root, matcher.as_ref());
+        let exported = export::export_note_content(&content, &self.vault_root, &full_path, &index);
+        export::ExportNoteResult { path: path_with_ext, content: Some(exported), error: None }
+    }
+
+    #[tool(description = "Export the entire vault into portable standard markdown at 'destination', rewriting wikilinks and inlining embeds as in export_note. Relative directory structure is preserved; files are processed in parallel. Returns the number of files exported and any per-file errors.")]
+    pub fn export_vault(
+        &self,
+        params: Parameters<ExportVaultRequest>,
+    ) -> Json<export::ExportVaultResult> {
+        Json(self.export_vault_impl(params.0))
+    }
+
+    pub fn export_vault_impl(&self, ExportVaultRequest { destination }: ExportVaultRequest) -> export::ExportVaultResult {
+        let matcher = path_matcher::build_matcher(&[], &[], &self.default_exclude_patterns);
+        export::export_vault(&self.vault_root, &PathBuf::from(destination), matcher.as_ref())
     }
 }
 
@@ -1272,3 +2757,82 @@ impl ServerHandler for ObsidianService {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sets up a throwaway vault under the system temp dir, removed on drop.
+    struct TempVault {
+        root: PathBuf,
+    }
+
+    impl TempVault {
+        fn new() -> Self {
+            let root = std::env::temp_dir().join(format!(
+                "mcp_obsidian_notes_test_{}_{}",
+                std::process::id(),
+                Uuid::new_v4()
+            ));
+            fs::create_dir_all(&root).unwrap();
+            TempVault { root }
+        }
+
+        fn write(&self, rel_path: &str, content: &str) {
+            let full = self.root.join(rel_path);
+            if let Some(parent) = full.parent() {
+                fs::create_dir_all(parent).unwrap();
+            }
+            fs::write(full, content).unwrap();
+        }
+
+        fn read(&self, rel_path: &str) -> String {
+            fs::read_to_string(self.root.join(rel_path)).unwrap()
+        }
+
+        fn service(&self) -> ObsidianService {
+            ObsidianService::new(
+                self.root.to_str().unwrap(),
+                None, None, None, None, None, None,
+                Vec::new(),
+                JsonMap::new(),
+            ).unwrap()
+        }
+    }
+
+    impl Drop for TempVault {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.root);
+        }
+    }
+
+    #[test]
+    fn test_rewrite_wikilinks_updates_matching_targets() {
+        let vault = TempVault::new();
+        vault.write("Notes/old-name.md", "content");
+        vault.write("referrer.md", "See [[old-name]] and [[old-name|alias]] but not [[other]].");
+        let service = vault.service();
+
+        let (count, updated_files) = service.rewrite_wikilinks("Notes/old-name.md", "Notes/new-name.md");
+
+        assert_eq!(count, 2);
+        assert_eq!(updated_files, vec!["referrer.md".to_string()]);
+        let content = vault.read("referrer.md");
+        assert!(content.contains("[[new-name]]"));
+        assert!(content.contains("[[new-name|alias]]"));
+        assert!(content.contains("[[other]]"));
+    }
+
+    #[test]
+    fn test_rewrite_wikilinks_leaves_non_matching_links_alone() {
+        let vault = TempVault::new();
+        vault.write("referrer.md", "See [[unrelated]].");
+        let service = vault.service();
+
+        let (count, updated_files) = service.rewrite_wikilinks("Notes/old-name.md", "Notes/new-name.md");
+
+        assert_eq!(count, 0);
+        assert!(updated_files.is_empty());
+        assert_eq!(vault.read("referrer.md"), "See [[unrelated]].");
+    }
+}
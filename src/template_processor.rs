@@ -1,4 +1,4 @@
-use chrono::{Local, Duration, Months};
+use chrono::{Local, Duration, Months, NaiveDate, NaiveDateTime, TimeZone};
 use regex::Regex;
 use std::collections::HashMap;
 
@@ -12,29 +12,41 @@ enum ExpressionType {
     DateExpression {
         format: String,
         offset: Option<String>,
+        base: Option<String>,
     },
     NumericExpression(String),
     SimpleVariable(String),
 }
 
+/// A token in a numeric expression. `Op('u')` is unary minus, kept distinct
+/// from binary `Op('-')` so the shunting-yard pass can give it its own
+/// (higher) precedence and `eval_rpn` can tell it applies to one operand.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NumericToken {
+    Num(f64),
+    Op(char),
+    LParen,
+    RParen,
+}
+
 impl TemplateProcessor {
     /// Process a template string, replacing all expressions
     /// Processing order:
-    /// 1. Date expressions ({{date:FORMAT| OFFSET}})
+    /// 1. Date expressions ({{date:FORMAT| OFFSET| BASE}})
     /// 2. Numeric calculations ({{2 + 3}})
     /// 3. Simple variable substitution ({{variable}})
     pub fn process(template: &str, variables: &HashMap<String, String>) -> String {
         // Step 1: Find all expressions that need processing
         let expressions = Self::find_expressions(template);
-        
+
         // Step 2: Process each expression type
         let mut result = template.to_string();
-        
+
         // Process date expressions first
         for expr in &expressions {
-            if let ExpressionType::DateExpression { format, offset } = expr {
-                if let Ok(replacement) = Self::evaluate_date_expression(format, offset.as_deref()) {
-                    let pattern = Self::build_date_pattern(format, offset.as_deref());
+            if let ExpressionType::DateExpression { format, offset, base } = expr {
+                if let Ok(replacement) = Self::evaluate_date_expression(format, offset.as_deref(), base.as_deref(), variables) {
+                    let pattern = Self::build_date_pattern(format, offset.as_deref(), base.as_deref());
                     result = result.replace(&pattern, &replacement);
                 }
                 // If evaluation fails, leave as-is (graceful failure)
@@ -98,74 +110,145 @@ impl TemplateProcessor {
         
         let rest = &expr[5..]; // Skip "date:"
         
-        // Split by "|" to separate format and offset
+        // Split by "|" to separate format, offset, and an optional base date
         let parts: Vec<&str> = rest.split('|').map(|s| s.trim()).collect();
-        
+
         if parts.is_empty() {
             return None;
         }
-        
+
         let format = parts[0].to_string();
         let offset = parts.get(1).map(|s| s.to_string());
-        
-        Some(ExpressionType::DateExpression { format, offset })
+        let base = parts.get(2).map(|s| s.to_string());
+
+        Some(ExpressionType::DateExpression { format, offset, base })
     }
-    
+
     /// Build the full pattern for a date expression
-    fn build_date_pattern(format: &str, offset: Option<&str>) -> String {
-        if let Some(off) = offset {
-            format!("{{{{date:{}| {}}}}}", format, off)
-        } else {
-            format!("{{{{date:{}}}}}", format)
+    fn build_date_pattern(format: &str, offset: Option<&str>, base: Option<&str>) -> String {
+        match (offset, base) {
+            (Some(off), Some(base)) => format!("{{{{date:{}| {}| {}}}}}", format, off, base),
+            (Some(off), None) => format!("{{{{date:{}| {}}}}}", format, off),
+            (None, _) => format!("{{{{date:{}}}}}", format),
         }
     }
-    
-    /// Evaluate a date expression
-    fn evaluate_date_expression(format: &str, offset: Option<&str>) -> Result<String, String> {
-        // Get base date (now)
-        let mut date = Local::now();
-        
+
+    /// Resolves `base`, which is either a name in `variables` or an inline
+    /// ISO-8601 date/datetime string, to a local date-time; falls back to
+    /// `now()` when absent or blank.
+    fn resolve_base_date(base: Option<&str>, variables: &HashMap<String, String>) -> Result<chrono::DateTime<Local>, String> {
+        let Some(base) = base.map(str::trim).filter(|s| !s.is_empty()) else {
+            return Ok(Local::now());
+        };
+        let raw = variables.get(base).map(|s| s.as_str()).unwrap_or(base);
+        let naive = Self::parse_flexible_date(raw)?;
+        Local.from_local_datetime(&naive)
+            .single()
+            .ok_or_else(|| format!("Ambiguous or invalid local time for base date: {}", raw))
+    }
+
+    /// Parses a base date string, accepting a bare `YYYY-MM-DD` date or a
+    /// space/`T`-separated datetime (matching `NaiveDateTime::to_string()`'s
+    /// round-trippable output either way).
+    fn parse_flexible_date(raw: &str) -> Result<NaiveDateTime, String> {
+        if let Ok(ndt) = raw.parse::<NaiveDateTime>() {
+            return Ok(ndt);
+        }
+        if let Ok(ndt) = raw.replacen('T', " ", 1).parse::<NaiveDateTime>() {
+            return Ok(ndt);
+        }
+        if let Ok(nd) = raw.parse::<NaiveDate>() {
+            return nd.and_hms_opt(0, 0, 0).ok_or_else(|| format!("Invalid base date: {}", raw));
+        }
+        Err(format!("Invalid base date: {}", raw))
+    }
+
+    /// Evaluate a date expression, anchored on `base` (a variable name or
+    /// inline ISO-8601 date/datetime) when present, or `now()` otherwise.
+    /// Reached from `create_note_from_template_impl` via
+    /// `TemplateProcessor::process`, so `{{date:FORMAT|OFFSET|BASE}}` can
+    /// anchor on a caller-supplied `variables` entry, not just `now()`.
+    fn evaluate_date_expression(
+        format: &str,
+        offset: Option<&str>,
+        base: Option<&str>,
+        variables: &HashMap<String, String>,
+    ) -> Result<String, String> {
+        let mut date = Self::resolve_base_date(base, variables)?;
+
         // Apply offset if present
         if let Some(off) = offset {
             date = Self::apply_date_offset(date, off)?;
         }
-        
+
         // Convert moment.js format to chrono format and format the date
         let chrono_format = Self::moment_to_chrono_format(format)?;
         Ok(date.format(&chrono_format).to_string())
     }
     
-    /// Apply date offset like "-7d", "+1w", "-2m", "+1y"
+    /// Resolves an offset unit alias (terse letter or word form) to its
+    /// canonical unit. `m` alone means minutes; months require a word form
+    /// (`mo`/`month`/`months`) or the dedicated `M` letter, so minute/month
+    /// never collide.
+    fn offset_unit_for_alias(alias: &str) -> &'static str {
+        match alias {
+            "s" | "sec" | "seconds" => "seconds",
+            "m" | "min" | "minutes" => "minutes",
+            "h" | "hr" | "hours" => "hours",
+            "d" | "day" | "days" => "days",
+            "w" | "week" | "weeks" => "weeks",
+            "M" | "mo" | "month" | "months" => "months",
+            "y" | "yr" | "year" | "years" => "years",
+            _ => unreachable!("alias not in offset_regex alternation: {}", alias),
+        }
+    }
+
+    /// Apply a sequence of date offset terms, e.g. "-7d", "+1w -2d", "+3h",
+    /// applying each term in order left to right. Reached from
+    /// `create_note_from_template_impl` via `TemplateProcessor::process`, so
+    /// `{{date:FORMAT| +3h}}` in a template now actually shifts the date.
     fn apply_date_offset(date: chrono::DateTime<Local>, offset: &str) -> Result<chrono::DateTime<Local>, String> {
         let offset = offset.trim();
         if offset.is_empty() {
             return Ok(date);
         }
-        
-        // Parse offset: [+-]?[0-9]+[dwmy]
-        let offset_regex = Regex::new(r"([+-]?)(\d+)([dwmy])").unwrap();
-        
+
+        // Parse offset: [+-]?[0-9]+<unit>, unit aliases ordered longest
+        // first so e.g. "seconds" isn't matched as just "s".
+        let offset_regex = Regex::new(
+            r"([+-]?)(\d+)\s*(seconds|minutes|months|hours|weeks|month|years|days|week|year|sec|min|day|hr|mo|yr|s|m|h|d|w|M|y)"
+        ).unwrap();
+
         let mut result_date = date;
-        
+
         for cap in offset_regex.captures_iter(offset) {
             let sign = cap.get(1).map(|m| m.as_str()).unwrap_or("+");
             let amount: i64 = cap.get(2)
                 .and_then(|m| m.as_str().parse().ok())
                 .ok_or_else(|| format!("Invalid offset amount in: {}", offset))?;
-            let unit = cap.get(3)
-                .and_then(|m| m.as_str().chars().next())
+            let alias = cap.get(3)
+                .map(|m| m.as_str())
                 .ok_or_else(|| format!("Invalid offset unit in: {}", offset))?;
-            
+
             let actual_amount = if sign == "-" { -amount } else { amount };
-            
-            match unit {
-                'd' => {
+
+            match Self::offset_unit_for_alias(alias) {
+                "seconds" => {
+                    result_date = result_date + Duration::seconds(actual_amount);
+                }
+                "minutes" => {
+                    result_date = result_date + Duration::minutes(actual_amount);
+                }
+                "hours" => {
+                    result_date = result_date + Duration::hours(actual_amount);
+                }
+                "days" => {
                     result_date = result_date + Duration::days(actual_amount);
                 }
-                'w' => {
+                "weeks" => {
                     result_date = result_date + Duration::weeks(actual_amount);
                 }
-                'm' => {
+                "months" => {
                     // Months need special handling - chrono supports both positive and negative
                     if actual_amount >= 0 {
                         let months = Months::new(actual_amount as u32);
@@ -178,7 +261,7 @@ impl TemplateProcessor {
                             .ok_or_else(|| format!("Invalid month offset: {}", actual_amount))?;
                     }
                 }
-                'y' => {
+                "years" => {
                     // Years as months
                     if actual_amount >= 0 {
                         let months = Months::new((actual_amount * 12) as u32);
@@ -190,21 +273,39 @@ impl TemplateProcessor {
                             .ok_or_else(|| format!("Invalid year offset: {}", actual_amount))?;
                     }
                 }
-                _ => return Err(format!("Unknown offset unit: {}", unit)),
+                other => return Err(format!("Unknown offset unit: {}", other)),
             }
         }
-        
+
         Ok(result_date)
     }
     
     /// Convert moment.js format string to chrono format string
     /// Supports: YYYY, MM, DD, HH, mm, ss, ww, ddd, dddd, MMM, MMMM, etc.
+    /// The FORMAT segment of `{{date:FORMAT| OFFSET| BASE}}` is moment.js
+    /// syntax, converted here before `.format()` runs; this is exercised now
+    /// that `create_note_from_template_impl` calls `TemplateProcessor::process`.
     fn moment_to_chrono_format(moment_format: &str) -> Result<String, String> {
         let mut result = String::new();
         let mut chars = moment_format.chars().peekable();
         
         while let Some(ch) = chars.next() {
             match ch {
+                '[' => {
+                    // moment.js literal escape: copy verbatim up to the
+                    // matching ']', doubling any '%' so chrono treats it as
+                    // literal text rather than a format specifier.
+                    for literal_ch in chars.by_ref() {
+                        if literal_ch == ']' {
+                            break;
+                        }
+                        if literal_ch == '%' {
+                            result.push_str("%%");
+                        } else {
+                            result.push(literal_ch);
+                        }
+                    }
+                }
                 'Y' => {
                     // YYYY = 4-digit year, YY = 2-digit year
                     let mut count = 1;
@@ -324,6 +425,11 @@ impl TemplateProcessor {
                     // A = AM/PM (same as 'a')
                     result.push_str("%p");
                 }
+                '%' => {
+                    // Guard a literal '%' in the input so chrono doesn't
+                    // treat it as the start of a format specifier.
+                    result.push_str("%%");
+                }
                 _ => {
                     // Literal character - escape if needed
                     if ch.is_alphanumeric() {
@@ -350,47 +456,154 @@ impl TemplateProcessor {
         has_operator && has_number
     }
     
-    /// Evaluate a numeric expression (basic math)
-    fn evaluate_numeric_expression(expr: &str) -> Result<String, String> {
-        // Very basic implementation - just for simple arithmetic
-        // For production, consider using a proper expression evaluator
-        
-        // Remove whitespace
-        let expr = expr.replace(' ', "");
-        
-        // Try to parse and evaluate simple expressions
-        // This is a simplified evaluator - handles: number op number
-        let re = Regex::new(r"(-?\d+\.?\d*)\s*([+\-*/%])\s*(-?\d+\.?\d*)").unwrap();
-        
-        if let Some(cap) = re.captures(&expr) {
-            let left: f64 = cap.get(1).unwrap().as_str().parse()
-                .map_err(|_| "Invalid number")?;
-            let op = cap.get(2).unwrap().as_str();
-            let right: f64 = cap.get(3).unwrap().as_str().parse()
-                .map_err(|_| "Invalid number")?;
-            
-            let result = match op {
-                "+" => left + right,
-                "-" => left - right,
-                "*" => left * right,
-                "/" => {
-                    if right == 0.0 {
-                        return Err("Division by zero".to_string());
+    /// Tokenizes a numeric expression into numbers, `+ - * / %` operators,
+    /// and parens. A `-` is tokenized as unary (`Op('u')`) when it appears
+    /// at the start of the expression or right after another operator or
+    /// `(`; otherwise it's the binary subtraction operator.
+    fn tokenize_numeric_expression(expr: &str) -> Result<Vec<NumericToken>, String> {
+        let mut tokens = Vec::new();
+        let chars: Vec<char> = expr.chars().collect();
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            if c.is_whitespace() {
+                i += 1;
+                continue;
+            }
+            if c.is_ascii_digit() || c == '.' {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                let num_str: String = chars[start..i].iter().collect();
+                let num: f64 = num_str.parse().map_err(|_| format!("Invalid number: {}", num_str))?;
+                tokens.push(NumericToken::Num(num));
+                continue;
+            }
+            match c {
+                '+' | '*' | '/' | '%' => tokens.push(NumericToken::Op(c)),
+                '-' => {
+                    let is_unary = matches!(
+                        tokens.last(),
+                        None | Some(NumericToken::Op(_)) | Some(NumericToken::LParen)
+                    );
+                    tokens.push(NumericToken::Op(if is_unary { 'u' } else { '-' }));
+                }
+                '(' => tokens.push(NumericToken::LParen),
+                ')' => tokens.push(NumericToken::RParen),
+                _ => return Err(format!("Unexpected character in expression: {}", c)),
+            }
+            i += 1;
+        }
+        Ok(tokens)
+    }
+
+    /// Operator precedence: `* / %` = 2, `+ -` = 1, unary minus = 3 (binds
+    /// tighter than anything else so `-2 * 3` negates 2, not `2 * 3`).
+    fn numeric_op_precedence(op: char) -> u8 {
+        match op {
+            'u' => 3,
+            '*' | '/' | '%' => 2,
+            '+' | '-' => 1,
+            _ => 0,
+        }
+    }
+
+    /// Shunting-yard: converts infix tokens to RPN, popping operators of
+    /// higher-or-equal precedence before pushing a new one, and flushing
+    /// back to the matching `(` on `)`.
+    fn numeric_tokens_to_rpn(tokens: Vec<NumericToken>) -> Result<Vec<NumericToken>, String> {
+        let mut output = Vec::new();
+        let mut ops: Vec<NumericToken> = Vec::new();
+        for token in tokens {
+            match token {
+                NumericToken::Num(_) => output.push(token),
+                NumericToken::Op(op) => {
+                    while let Some(NumericToken::Op(top)) = ops.last() {
+                        if op != 'u' && Self::numeric_op_precedence(*top) >= Self::numeric_op_precedence(op) {
+                            output.push(ops.pop().unwrap());
+                        } else {
+                            break;
+                        }
                     }
-                    left / right
+                    ops.push(token);
                 }
-                "%" => ((left as i64) % (right as i64)) as f64,
-                _ => return Err(format!("Unknown operator: {}", op)),
-            };
-            
-            // Format result - remove .0 for integers
-            if result.fract() == 0.0 {
-                Ok(result as i64 as i32).map(|n| n.to_string())
-            } else {
-                Ok(result.to_string())
+                NumericToken::LParen => ops.push(token),
+                NumericToken::RParen => loop {
+                    match ops.pop() {
+                        Some(NumericToken::LParen) => break,
+                        Some(op) => output.push(op),
+                        None => return Err("Mismatched parentheses".to_string()),
+                    }
+                },
+            }
+        }
+        while let Some(op) = ops.pop() {
+            if matches!(op, NumericToken::LParen | NumericToken::RParen) {
+                return Err("Mismatched parentheses".to_string());
             }
+            output.push(op);
+        }
+        Ok(output)
+    }
+
+    /// Evaluates an RPN token stream with a value stack.
+    fn eval_numeric_rpn(rpn: Vec<NumericToken>) -> Result<f64, String> {
+        let mut stack: Vec<f64> = Vec::new();
+        for token in rpn {
+            match token {
+                NumericToken::Num(n) => stack.push(n),
+                NumericToken::Op('u') => {
+                    let v = stack.pop().ok_or("Expression not supported")?;
+                    stack.push(-v);
+                }
+                NumericToken::Op(op) => {
+                    let right = stack.pop().ok_or("Expression not supported")?;
+                    let left = stack.pop().ok_or("Expression not supported")?;
+                    let result = match op {
+                        '+' => left + right,
+                        '-' => left - right,
+                        '*' => left * right,
+                        '/' => {
+                            if right == 0.0 {
+                                return Err("Division by zero".to_string());
+                            }
+                            left / right
+                        }
+                        '%' => {
+                            if right == 0.0 {
+                                return Err("Division by zero".to_string());
+                            }
+                            left % right
+                        }
+                        _ => return Err(format!("Unknown operator: {}", op)),
+                    };
+                    stack.push(result);
+                }
+                NumericToken::LParen | NumericToken::RParen => return Err("Expression not supported".to_string()),
+            }
+        }
+        if stack.len() != 1 {
+            return Err("Expression not supported".to_string());
+        }
+        Ok(stack[0])
+    }
+
+    /// Evaluate a numeric expression with full operator precedence,
+    /// parentheses, and unary minus (e.g. `(1 + 2) * 7`, `-2 * 3`).
+    fn evaluate_numeric_expression(expr: &str) -> Result<String, String> {
+        let tokens = Self::tokenize_numeric_expression(expr)?;
+        if tokens.is_empty() {
+            return Err("Expression not supported".to_string());
+        }
+        let rpn = Self::numeric_tokens_to_rpn(tokens)?;
+        let result = Self::eval_numeric_rpn(rpn)?;
+
+        // Format result - remove .0 for integers
+        if result.fract() == 0.0 {
+            Ok((result as i64).to_string())
         } else {
-            Err("Expression not supported".to_string())
+            Ok(result.to_string())
         }
     }
     
@@ -418,5 +631,84 @@ mod tests {
         let result = TemplateProcessor::evaluate_numeric_expression("2 + 3");
         assert_eq!(result, Ok("5".to_string()));
     }
+
+    #[test]
+    fn test_numeric_expression_operator_precedence() {
+        let result = TemplateProcessor::evaluate_numeric_expression("2 + 3 * 4");
+        assert_eq!(result, Ok("14".to_string()));
+    }
+
+    #[test]
+    fn test_numeric_expression_parentheses() {
+        let result = TemplateProcessor::evaluate_numeric_expression("(1 + 2) * 7");
+        assert_eq!(result, Ok("21".to_string()));
+    }
+
+    #[test]
+    fn test_numeric_expression_unary_minus() {
+        let result = TemplateProcessor::evaluate_numeric_expression("-2 * 3");
+        assert_eq!(result, Ok("-6".to_string()));
+    }
+
+    #[test]
+    fn test_numeric_expression_division_by_zero() {
+        let result = TemplateProcessor::evaluate_numeric_expression("1 / 0");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_numeric_expression_mismatched_parentheses() {
+        let result = TemplateProcessor::evaluate_numeric_expression("(1 + 2");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_apply_date_offset_days() {
+        let base = Local.from_local_datetime(
+            &"2024-01-15 00:00:00".parse::<NaiveDateTime>().unwrap()
+        ).unwrap();
+        let result = TemplateProcessor::apply_date_offset(base, "-7d").unwrap();
+        assert_eq!(result.format("%Y-%m-%d").to_string(), "2024-01-08");
+    }
+
+    #[test]
+    fn test_apply_date_offset_compound() {
+        let base = Local.from_local_datetime(
+            &"2024-01-15 00:00:00".parse::<NaiveDateTime>().unwrap()
+        ).unwrap();
+        let result = TemplateProcessor::apply_date_offset(base, "+1w -2d").unwrap();
+        assert_eq!(result.format("%Y-%m-%d").to_string(), "2024-01-20");
+    }
+
+    #[test]
+    fn test_apply_date_offset_months() {
+        let base = Local.from_local_datetime(
+            &"2024-01-31 00:00:00".parse::<NaiveDateTime>().unwrap()
+        ).unwrap();
+        let result = TemplateProcessor::apply_date_offset(base, "+1M").unwrap();
+        assert_eq!(result.format("%Y-%m-%d").to_string(), "2024-02-29");
+    }
+
+    #[test]
+    fn test_evaluate_date_expression_anchors_on_base_variable() {
+        let mut variables = HashMap::new();
+        variables.insert("due".to_string(), "2024-06-01".to_string());
+        let result = TemplateProcessor::evaluate_date_expression(
+            "YYYY-MM-DD", Some("+1d"), Some("due"), &variables,
+        );
+        assert_eq!(result, Ok("2024-06-02".to_string()));
+    }
+
+    #[test]
+    fn test_moment_to_chrono_format_basic() {
+        let result = TemplateProcessor::moment_to_chrono_format("YYYY-MM-DD HH:mm:ss").unwrap();
+        assert_eq!(result, "%Y-%m-%d %H:%M:%S");
+    }
+
+    #[test]
+    fn test_moment_to_chrono_format_literal_escape() {
+        let result = TemplateProcessor::moment_to_chrono_format("[Week] ww").unwrap();
+        assert_eq!(result, "Week %V");
+    }
 }
 
@@ -0,0 +1,263 @@
+// Versioned, gzipped-tar export/import of an entire vault.
+//
+// Shells out to nothing here (unlike git_sync) - the tar/gzip work is done
+// in-process via the `tar`/`flate2` crates so a dump can be produced even
+// when the vault isn't a git repository.
+
+use chrono::Local;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const DUMP_FORMAT_VERSION: u32 = 1;
+const METADATA_ENTRY: &str = ".mcp_dump_metadata.json";
+const PATH_CONFIG_ENTRY: &str = ".mcp_dump_pathconfig.json";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DumpMetadata {
+    format_version: u32,
+    crate_version: String,
+    created_at: String,
+    file_count: usize,
+}
+
+/// The vault's configured sub-paths, archived alongside the notes themselves
+/// so a restored vault can recover its daily/weekly/monthly/templates
+/// locations even if the destination's own config was never set up.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct DumpPathConfig {
+    pub daily_notes_path: Option<String>,
+    pub weekly_notes_path: Option<String>,
+    pub monthly_notes_path: Option<String>,
+    pub templates_path: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct DumpResult {
+    pub success: bool,
+    #[schemars(description = "Path to the created archive")]
+    pub output_path: Option<String>,
+    #[schemars(description = "Number of files written into the archive")]
+    pub files_archived: usize,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct LoadResult {
+    pub success: bool,
+    #[schemars(description = "Number of files extracted from the archive")]
+    pub files_restored: usize,
+    #[schemars(description = "Dump format version the archive was created with")]
+    pub format_version: Option<u32>,
+    #[schemars(description = "The source vault's daily/weekly/monthly/templates paths, if the archive carried a path-config sidecar")]
+    pub path_config: Option<DumpPathConfig>,
+    pub error: Option<String>,
+}
+
+impl DumpResult {
+    fn failure(error: impl Into<String>) -> Self {
+        Self {
+            success: false,
+            output_path: None,
+            files_archived: 0,
+            error: Some(error.into()),
+        }
+    }
+}
+
+impl LoadResult {
+    fn failure(error: impl Into<String>) -> Self {
+        Self {
+            success: false,
+            files_restored: 0,
+            format_version: None,
+            path_config: None,
+            error: Some(error.into()),
+        }
+    }
+}
+
+/// Writes a tar entry's bytes with a standard GNU header (0644, checksummed).
+fn append_json_entry<W: std::io::Write>(
+    builder: &mut tar::Builder<W>,
+    entry_name: &str,
+    json: &[u8],
+) -> std::io::Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(json.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append_data(&mut header, entry_name, json)
+}
+
+/// Write every file under `vault_root` (plus `.mcp_dump_metadata.json` and
+/// `.mcp_dump_pathconfig.json` entries, written first so a streaming
+/// consumer can read them before the rest of the archive) into a gzipped tar
+/// archive at `output_path`. Written to a temp file in the destination's own
+/// directory and renamed into place, so a crash mid-dump can't leave a
+/// corrupt/partial archive at `output_path` (mirrors note_history::atomic_write).
+pub fn create_dump(vault_root: &Path, output_path: &Path, path_config: &DumpPathConfig) -> DumpResult {
+    let file_count = match count_entries(vault_root) {
+        Ok(n) => n,
+        Err(e) => return DumpResult::failure(e),
+    };
+
+    let metadata = DumpMetadata {
+        format_version: DUMP_FORMAT_VERSION,
+        crate_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: Local::now().to_rfc3339(),
+        file_count,
+    };
+    let metadata_json = match serde_json::to_vec_pretty(&metadata) {
+        Ok(v) => v,
+        Err(e) => return DumpResult::failure(format!("Failed to serialize dump metadata: {}", e)),
+    };
+    let path_config_json = match serde_json::to_vec_pretty(path_config) {
+        Ok(v) => v,
+        Err(e) => return DumpResult::failure(format!("Failed to serialize dump path config: {}", e)),
+    };
+
+    let dump_dir = output_path.parent().unwrap_or_else(|| Path::new("."));
+    let dump_file_name = output_path.file_name().and_then(|n| n.to_str()).unwrap_or("dump.tar.gz");
+    let tmp_path = dump_dir.join(format!(".{}.tmp-{}", dump_file_name, timestamp_now()));
+
+    let result = (|| -> Result<(), String> {
+        let file = File::create(&tmp_path)
+            .map_err(|e| format!("Failed to create {}: {}", tmp_path.display(), e))?;
+        let encoder = GzEncoder::new(file, Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+
+        append_json_entry(&mut builder, METADATA_ENTRY, &metadata_json)
+            .map_err(|e| format!("Failed to write dump metadata: {}", e))?;
+        append_json_entry(&mut builder, PATH_CONFIG_ENTRY, &path_config_json)
+            .map_err(|e| format!("Failed to write dump path config: {}", e))?;
+
+        builder.append_dir_all(".", vault_root)
+            .map_err(|e| format!("Failed to archive vault: {}", e))?;
+
+        builder.into_inner()
+            .and_then(|enc| enc.finish())
+            .map_err(|e| format!("Failed to finalize archive: {}", e))?;
+        Ok(())
+    })();
+
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&tmp_path);
+        return DumpResult::failure(e);
+    }
+
+    if let Err(e) = std::fs::rename(&tmp_path, output_path) {
+        let _ = std::fs::remove_file(&tmp_path);
+        return DumpResult::failure(format!("Failed to finalize {}: {}", output_path.display(), e));
+    }
+
+    DumpResult {
+        success: true,
+        output_path: Some(output_path.to_string_lossy().to_string()),
+        files_archived: file_count,
+        error: None,
+    }
+}
+
+fn timestamp_now() -> String {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis().to_string())
+        .unwrap_or_else(|_| "0".to_string())
+}
+
+/// Extract a dump archive into `vault_root`, overwriting existing files only
+/// when `overwrite` is true.
+pub fn load_dump(vault_root: &Path, input_path: &Path, overwrite: bool) -> LoadResult {
+    let file = match File::open(input_path) {
+        Ok(f) => f,
+        Err(e) => return LoadResult::failure(format!("Failed to open {}: {}", input_path.display(), e)),
+    };
+    let decoder = GzDecoder::new(file);
+    let mut archive = tar::Archive::new(decoder);
+
+    let entries = match archive.entries() {
+        Ok(e) => e,
+        Err(e) => return LoadResult::failure(format!("Failed to read archive: {}", e)),
+    };
+
+    let mut format_version = None;
+    let mut path_config = None;
+    let mut files_restored = 0usize;
+
+    for entry in entries {
+        let mut entry = match entry {
+            Ok(e) => e,
+            Err(e) => return LoadResult::failure(format!("Failed to read archive entry: {}", e)),
+        };
+
+        let path = match entry.path() {
+            Ok(p) => p.to_path_buf(),
+            Err(e) => return LoadResult::failure(format!("Invalid entry path in archive: {}", e)),
+        };
+
+        if path.to_string_lossy() == METADATA_ENTRY {
+            let mut buf = Vec::new();
+            if let Err(e) = std::io::Read::read_to_end(&mut entry, &mut buf) {
+                return LoadResult::failure(format!("Failed to read dump metadata: {}", e));
+            }
+            let metadata: DumpMetadata = match serde_json::from_slice(&buf) {
+                Ok(m) => m,
+                Err(e) => return LoadResult::failure(format!("Invalid dump metadata: {}", e)),
+            };
+            if metadata.format_version != DUMP_FORMAT_VERSION {
+                return LoadResult::failure(format!(
+                    "Incompatible dump format version {} (this build supports version {})",
+                    metadata.format_version, DUMP_FORMAT_VERSION
+                ));
+            }
+            format_version = Some(metadata.format_version);
+            continue;
+        }
+
+        if path.to_string_lossy() == PATH_CONFIG_ENTRY {
+            let mut buf = Vec::new();
+            if let Err(e) = std::io::Read::read_to_end(&mut entry, &mut buf) {
+                return LoadResult::failure(format!("Failed to read dump path config: {}", e));
+            }
+            path_config = serde_json::from_slice::<DumpPathConfig>(&buf).ok();
+            continue;
+        }
+
+        let dest = vault_root.join(&path);
+        if !overwrite && dest.exists() {
+            continue;
+        }
+        if let Err(e) = entry.unpack(&dest) {
+            return LoadResult::failure(format!("Failed to extract {}: {}", path.display(), e));
+        }
+        if dest.is_file() {
+            files_restored += 1;
+        }
+    }
+
+    LoadResult {
+        success: true,
+        files_restored,
+        format_version,
+        path_config,
+        error: None,
+    }
+}
+
+fn count_entries(vault_root: &Path) -> Result<usize, String> {
+    let mut count = 0;
+    for entry in walkdir::WalkDir::new(vault_root)
+        .into_iter()
+        .filter_map(|e| e.ok())
+    {
+        if entry.path().is_file() {
+            count += 1;
+        }
+    }
+    Ok(count)
+}